@@ -0,0 +1,111 @@
+#ifndef RKG_UTILS_H
+#define RKG_UTILS_H
+
+/* Generated by cbindgen from src/capi.rs, don't edit by hand */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * A parsed ghost with its inputs already merged, so frames can be looked up quickly
+ */
+typedef struct RkgGhost RkgGhost;
+
+/**
+ * A time as the game shows it, all zeroes if it couldn't be read
+ */
+typedef struct {
+  uint8_t minutes;
+  uint8_t seconds;
+  uint16_t milliseconds;
+} RkgTime;
+
+/**
+ * Controller state during a single frame
+ */
+typedef struct {
+  /**
+   * Face button bits as stored in the file: 0x01 accelerator, 0x02 brake, 0x04 item,
+   * 0x08 drift (always with the brake)
+   */
+  uint8_t buttons;
+  /**
+   * -7 to 7
+   */
+  int8_t stick_x;
+  /**
+   * -7 to 7
+   */
+  int8_t stick_y;
+  /**
+   * 0 none, 1 up, 2 down, 3 left, 4 right
+   */
+  uint8_t dpad;
+} RkgFrame;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Parses a whole ghost file. Returns NULL if the data isn't a valid ghost.
+ *
+ * # Safety
+ * `data` must point to `length` readable bytes, which are only used during the call.
+ */
+RkgGhost *rkg_ghost_parse(const uint8_t *data, size_t length);
+
+/**
+ * Releases a ghost from `rkg_ghost_parse`. Does nothing with NULL.
+ *
+ * # Safety
+ * `ghost` must come from `rkg_ghost_parse` and not have been freed yet.
+ */
+void rkg_ghost_free(RkgGhost *ghost);
+
+/**
+ * # Safety
+ * `ghost` must be NULL or a live handle from `rkg_ghost_parse`.
+ */
+RkgTime rkg_ghost_finish_time(const RkgGhost *ghost);
+
+/**
+ * # Safety
+ * `ghost` must be NULL or a live handle from `rkg_ghost_parse`.
+ */
+uint8_t rkg_ghost_lap_count(const RkgGhost *ghost);
+
+/**
+ * Split time of lap `lap`, counting from 0. All zeroes past the last lap.
+ *
+ * # Safety
+ * `ghost` must be NULL or a live handle from `rkg_ghost_parse`.
+ */
+RkgTime rkg_ghost_lap_time(const RkgGhost *ghost, uint8_t lap);
+
+/**
+ * Amount of frames of input, 0 for NULL
+ *
+ * # Safety
+ * `ghost` must be NULL or a live handle from `rkg_ghost_parse`.
+ */
+uint32_t rkg_ghost_frame_count(const RkgGhost *ghost);
+
+/**
+ * Writes the controller state of `frame` to `out`. Frames are 0-based, so valid ones are
+ * `frame < rkg_ghost_frame_count(ghost)`. Returns false, leaving `out` untouched, for any other
+ * frame or if a pointer is NULL.
+ *
+ * # Safety
+ * `ghost` must be NULL or a live handle from `rkg_ghost_parse`, `out` NULL or writable.
+ */
+bool rkg_ghost_frame_at(const RkgGhost *ghost, uint32_t frame, RkgFrame *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RKG_UTILS_H */
@@ -0,0 +1,4 @@
+//! Links the `capi` feature of `rkg-utils` into C libraries, the functions live in
+//! `rkg_utils::capi`.
+
+pub use rkg_utils::capi::*;
@@ -0,0 +1,172 @@
+/*
+ * Parses every fixture in test_ghosts/ through the C ABI. From the repository root:
+ *
+ *   cargo build --manifest-path capi/Cargo.toml
+ *   cc -std=c99 -Wall -Wextra -Icapi/include capi/tests/test_ghosts.c \
+ *       capi/target/debug/librkg_utils.a -lpthread -ldl -lm -o target/test_ghosts
+ *   ./target/test_ghosts test_ghosts
+ */
+
+#include <dirent.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "rkg_utils.h"
+
+static int failures = 0;
+
+#define CHECK(condition)                                                           \
+    do {                                                                           \
+        if (!(condition)) {                                                        \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #condition); \
+            failures++;                                                            \
+        }                                                                          \
+    } while (0)
+
+static unsigned char *read_file(const char *path, size_t *length) {
+    FILE *file = fopen(path, "rb");
+    if (file == NULL) {
+        return NULL;
+    }
+
+    fseek(file, 0, SEEK_END);
+    long size = ftell(file);
+    fseek(file, 0, SEEK_SET);
+
+    unsigned char *data = malloc(size > 0 ? (size_t)size : 1);
+    *length = data != NULL ? fread(data, 1, (size_t)size, file) : 0;
+    fclose(file);
+    return data;
+}
+
+static unsigned int time_ms(RkgTime time) {
+    return time.minutes * 60000u + time.seconds * 1000u + time.milliseconds;
+}
+
+static void test_ghost(const char *path) {
+    size_t length = 0;
+    unsigned char *data = read_file(path, &length);
+    CHECK(data != NULL);
+    if (data == NULL) {
+        return;
+    }
+
+    RkgGhost *ghost = rkg_ghost_parse(data, length);
+    /* The parser copies what it needs, the buffer can go right away */
+    free(data);
+
+    if (ghost == NULL) {
+        /* Some fixtures are broken on purpose, they must fail cleanly */
+        printf("%s: rejected\n", path);
+        return;
+    }
+
+    RkgTime finish = rkg_ghost_finish_time(ghost);
+    if (strstr(path, "JC_LC") != NULL) {
+        CHECK(time_ms(finish) == 63904);
+        CHECK(rkg_ghost_lap_count(ghost) == 3);
+        CHECK(time_ms(rkg_ghost_lap_time(ghost, 0)) == 25540);
+        CHECK(rkg_ghost_frame_count(ghost) == 4071);
+    }
+    CHECK(time_ms(rkg_ghost_lap_time(ghost, rkg_ghost_lap_count(ghost))) == 0);
+
+    uint32_t frame_count = rkg_ghost_frame_count(ghost);
+    CHECK(frame_count > 0);
+
+    RkgFrame frame;
+    for (uint32_t index = 0; index < frame_count; index++) {
+        CHECK(rkg_ghost_frame_at(ghost, index, &frame));
+        CHECK(frame.stick_x >= -7 && frame.stick_x <= 7);
+        CHECK(frame.stick_y >= -7 && frame.stick_y <= 7);
+        CHECK(frame.dpad <= 4);
+    }
+    CHECK(!rkg_ghost_frame_at(ghost, frame_count, &frame));
+    CHECK(!rkg_ghost_frame_at(ghost, 0, NULL));
+
+    printf("%s: %02u:%02u.%03u, %u frames\n", path, finish.minutes, finish.seconds,
+           finish.milliseconds, frame_count);
+    rkg_ghost_free(ghost);
+}
+
+/* Truncated and corrupted copies of valid ghosts must come back as NULL, never crash */
+static void test_malformed(const char *directory) {
+    char path[4096];
+    size_t length = 0;
+
+    snprintf(path, sizeof path, "%s/JC_LC.rkg", directory);
+    unsigned char *data = read_file(path, &length);
+    CHECK(data != NULL && length > 0x100);
+    if (data == NULL || length <= 0x100) {
+        free(data);
+        return;
+    }
+
+    for (size_t truncated = 0; truncated < 0x100; truncated += 0x0C) {
+        CHECK(rkg_ghost_parse(data, truncated) == NULL);
+    }
+
+    /* Face input count far past the end of the data */
+    data[0x88] = 0xFF;
+    data[0x89] = 0xFF;
+    CHECK(rkg_ghost_parse(data, length) == NULL);
+    free(data);
+
+    snprintf(path, sizeof path, "%s/JC_LC_Compressed.rkg", directory);
+    data = read_file(path, &length);
+    CHECK(data != NULL && length > 0x100);
+    if (data == NULL || length <= 0x100) {
+        free(data);
+        return;
+    }
+
+    /* Yaz1 chunk copying from before the start of the output */
+    unsigned char *corrupt = malloc(length);
+    memcpy(corrupt, data, length);
+    memset(corrupt + 0x8C + 0x10, 0, 3);
+    CHECK(rkg_ghost_parse(corrupt, length) == NULL);
+
+    /* CTGP footer claiming to be bigger than the whole file */
+    memcpy(corrupt, data, length);
+    memset(corrupt + length - 0x0C, 0xFF, 4);
+    CHECK(rkg_ghost_parse(corrupt, length) == NULL);
+
+    free(corrupt);
+    free(data);
+}
+
+int main(int argc, char **argv) {
+    const char *directory = argc > 1 ? argv[1] : "test_ghosts";
+    DIR *dir = opendir(directory);
+    if (dir == NULL) {
+        fprintf(stderr, "Couldn't open %s\n", directory);
+        return 1;
+    }
+
+    int parsed = 0;
+    struct dirent *entry;
+    while ((entry = readdir(dir)) != NULL) {
+        const char *extension = strrchr(entry->d_name, '.');
+        if (extension == NULL || strcmp(extension, ".rkg") != 0) {
+            continue;
+        }
+
+        char path[4096];
+        snprintf(path, sizeof path, "%s/%s", directory, entry->d_name);
+        test_ghost(path);
+        parsed++;
+    }
+    closedir(dir);
+
+    CHECK(parsed > 0);
+    test_malformed(directory);
+    CHECK(rkg_ghost_parse(NULL, 0) == NULL);
+    CHECK(rkg_ghost_frame_count(NULL) == 0);
+    rkg_ghost_free(NULL);
+
+    if (failures > 0) {
+        fprintf(stderr, "%d checks failed\n", failures);
+        return 1;
+    }
+    return 0;
+}
@@ -0,0 +1,4 @@
+//! Links the `python` feature of `rkg-utils` into an extension module, the classes live in
+//! `rkg_utils::python`.
+
+pub use rkg_utils::python::*;
@@ -0,0 +1,355 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Display;
+
+use crate::{
+    ctgp_metadata::CTGPMetadata,
+    header::{
+        Header, HeaderError,
+        combo::{ComboIssue, ComboValidation},
+        mii::audit::MiiAuditIssue,
+    },
+    input_data::{
+        InputData, InputDataError,
+        face_input::{FaceButton, FaceButtonError, FaceInputError},
+    },
+    integrity::{IntegrityIssue, verify_integrity},
+    timing::{TimingIssue, TimingReport},
+};
+
+/// How a finding should be handled by a moderation pipeline
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Worth knowing, but fine on its own
+    Info,
+    /// Needs a human to look at it
+    Review,
+    /// The ghost can't be legitimate
+    Reject,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Info => write!(f, "info"),
+            Self::Review => write!(f, "review"),
+            Self::Reject => write!(f, "reject"),
+        }
+    }
+}
+
+/// Outcome of an audit, based on the most severe finding
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Verdict {
+    Accept,
+    Review,
+    Reject,
+}
+
+/// A single result of an audit. `code` is stable and meant for machines, `message` for people.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    code: &'static str,
+    severity: Severity,
+    message: String,
+}
+
+impl Finding {
+    fn new(code: &'static str, severity: Severity, message: impl ToString) -> Self {
+        Self {
+            code,
+            severity,
+            message: message.to_string(),
+        }
+    }
+
+    /// Dotted identifier such as `integrity.crc32_mismatch` or `ctgp.slowdown`
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "[{}] {}: {}", self.severity, self.code, self.message)
+    }
+}
+
+/// Every finding of every check that could run on a ghost
+#[derive(Clone, Debug, Default)]
+pub struct AuditReport {
+    findings: Vec<Finding>,
+}
+
+impl AuditReport {
+    pub fn findings(&self) -> &[Finding] {
+        &self.findings
+    }
+
+    pub fn has_code(&self, code: &str) -> bool {
+        self.findings.iter().any(|finding| finding.code == code)
+    }
+
+    pub fn verdict(&self) -> Verdict {
+        match self.findings.iter().map(|finding| finding.severity).max() {
+            Some(Severity::Reject) => Verdict::Reject,
+            Some(Severity::Review) => Verdict::Review,
+            Some(Severity::Info) | None => Verdict::Accept,
+        }
+    }
+
+    fn push(&mut self, code: &'static str, severity: Severity, message: impl ToString) {
+        self.findings.push(Finding::new(code, severity, message));
+    }
+}
+
+/// Runs every available check on a whole ghost file: structure and CRCs, header values, combo,
+/// Mii, input legality, timing consistency and CTGP's own flags. Works on files `Ghost::new`
+/// rejects; checks that need data which can't be read are skipped after reporting why.
+pub fn audit(data: &[u8]) -> AuditReport {
+    let mut report = AuditReport::default();
+
+    let integrity = verify_integrity(data);
+    let mut is_readable = true;
+    for issue in integrity.issues() {
+        let (code, severity) = integrity_code(issue);
+        is_readable &= severity != Severity::Reject;
+        report.push(code, severity, issue);
+    }
+    if !is_readable {
+        return report;
+    }
+
+    let header = match Header::new_with_combo_validation(&data[..0x88], ComboValidation::Warn) {
+        Ok(header) => Some(header),
+        Err(HeaderError::ComboError(error)) => {
+            report.push("header.invalid_combo", Severity::Reject, error);
+            None
+        }
+        Err(error) => {
+            report.push("header.invalid", Severity::Reject, error);
+            None
+        }
+    };
+
+    if let Some(header) = &header {
+        for issue in header.combo_issues() {
+            let (code, severity) = combo_code(&issue);
+            report.push(code, severity, issue);
+        }
+
+        for issue in header.mii().audit() {
+            report.push(mii_code(&issue), mii_severity(&issue), issue);
+        }
+    }
+
+    let input_data = match InputData::new(&data[0x88..]) {
+        Ok(input_data) => Some(input_data),
+        Err(InputDataError::FaceInputError(FaceInputError::InvalidButton(
+            FaceButtonError::IllegalDriftInput,
+        ))) => {
+            report.push(
+                "input.illegal_drift",
+                Severity::Reject,
+                "Drift flag is set without the brake button",
+            );
+            None
+        }
+        Err(error) => {
+            report.push("input.invalid", Severity::Reject, error);
+            None
+        }
+    };
+
+    if let Some(input_data) = &input_data {
+        let unknown_buttons = input_data
+            .face_inputs()
+            .iter()
+            .filter(|input| {
+                input
+                    .buttons()
+                    .iter()
+                    .any(|button| matches!(button, FaceButton::Unknown(_)))
+            })
+            .count();
+        if unknown_buttons > 0 {
+            report.push(
+                "input.unknown_button",
+                Severity::Review,
+                format!("{unknown_buttons} face inputs use undefined button bits"),
+            );
+        }
+    }
+
+    let is_ctgp = data[data.len() - 0x08..data.len() - 0x04] == *b"CKGD";
+    let ctgp_metadata = if is_ctgp {
+        match CTGPMetadata::new(data) {
+            Ok(ctgp_metadata) => Some(ctgp_metadata),
+            Err(error) => {
+                report.push("ctgp.invalid", Severity::Review, error);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    if let Some(ctgp_metadata) = &ctgp_metadata {
+        audit_ctgp_flags(ctgp_metadata, &mut report);
+    }
+
+    if let (Some(header), Some(input_data)) = (&header, &input_data) {
+        let timing = TimingReport::new(header, input_data, ctgp_metadata.as_ref());
+        for issue in timing.issues() {
+            report.push(timing_code(issue), Severity::Reject, issue);
+        }
+    }
+
+    report
+}
+
+fn audit_ctgp_flags(ctgp_metadata: &CTGPMetadata, report: &mut AuditReport) {
+    let flags = [
+        (
+            ctgp_metadata.dubious_ghost(),
+            "ctgp.dubious_ghost",
+            Severity::Review,
+            "CTGP marked the ghost as dubious",
+        ),
+        (
+            ctgp_metadata.final_lap_dubious_intersection(),
+            "ctgp.dubious_intersection",
+            Severity::Review,
+            "CTGP detected a dubious intersection on the final lap",
+        ),
+        (
+            ctgp_metadata.has_slowdown(),
+            "ctgp.slowdown",
+            Severity::Review,
+            "The game slowed down during the run",
+        ),
+        (
+            ctgp_metadata.has_rapidfire(),
+            "ctgp.rapidfire",
+            Severity::Review,
+            "Rapid fire inputs were detected",
+        ),
+        (
+            ctgp_metadata.went_oob(),
+            "ctgp.out_of_bounds",
+            Severity::Review,
+            "The player went out of bounds",
+        ),
+        (
+            ctgp_metadata.has_mii_data_replaced(),
+            "ctgp.mii_data_replaced",
+            Severity::Info,
+            "Mii data was replaced",
+        ),
+        (
+            ctgp_metadata.has_name_replaced(),
+            "ctgp.name_replaced",
+            Severity::Info,
+            "Mii name was replaced",
+        ),
+    ];
+
+    for (is_set, code, severity, message) in flags {
+        if is_set {
+            report.push(code, severity, message);
+        }
+    }
+}
+
+fn integrity_code(issue: &IntegrityIssue) -> (&'static str, Severity) {
+    match issue {
+        IntegrityIssue::FileTooShort { .. } => ("integrity.file_too_short", Severity::Reject),
+        IntegrityIssue::NotRKGD => ("integrity.not_rkgd", Severity::Reject),
+        IntegrityIssue::Crc32Mismatch { .. } => ("integrity.crc32_mismatch", Severity::Review),
+        IntegrityIssue::FooterCrc32Mismatch { .. } => {
+            ("integrity.footer_crc32_mismatch", Severity::Review)
+        }
+        IntegrityIssue::MiiCrc16Mismatch { .. } => {
+            ("integrity.mii_crc16_mismatch", Severity::Review)
+        }
+        IntegrityIssue::InputDataLengthMismatch { .. } => {
+            ("integrity.input_data_length_mismatch", Severity::Review)
+        }
+        IntegrityIssue::InputCountsExceedInputData { .. } => {
+            ("integrity.input_counts_exceed_input_data", Severity::Reject)
+        }
+        IntegrityIssue::MissingYaz1 { .. } => ("integrity.missing_yaz1", Severity::Reject),
+        IntegrityIssue::Yaz1DecompressionFailed { .. } => {
+            ("integrity.yaz1_decompression_failed", Severity::Reject)
+        }
+        IntegrityIssue::Truncated { .. } => ("integrity.truncated", Severity::Reject),
+        IntegrityIssue::MetadataSizeMismatch { .. } => {
+            ("integrity.metadata_size_mismatch", Severity::Review)
+        }
+        IntegrityIssue::TrailingData { .. } => ("integrity.trailing_data", Severity::Review),
+    }
+}
+
+fn combo_code(issue: &ComboIssue) -> (&'static str, Severity) {
+    match issue {
+        ComboIssue::ImpossibleCharacter(_) => ("combo.impossible_character", Severity::Reject),
+        ComboIssue::IncongruentWeightClasses { .. } => {
+            ("combo.incongruent_weight_classes", Severity::Reject)
+        }
+        // Mii data can be replaced after the race, e.g. by CTGP
+        ComboIssue::MiiOutfitMismatch { .. } => ("combo.mii_outfit_mismatch", Severity::Review),
+    }
+}
+
+fn timing_code(issue: &TimingIssue) -> &'static str {
+    match issue {
+        TimingIssue::InvalidFinishTime { .. } => "timing.invalid_finish_time",
+        TimingIssue::InvalidLapTime { .. } => "timing.invalid_lap_time",
+        TimingIssue::LapSumMismatch { .. } => "timing.lap_sum_mismatch",
+        TimingIssue::ExactFinishTimeMismatch { .. } => "timing.exact_finish_time_mismatch",
+        TimingIssue::ExactLapTimeMismatch { .. } => "timing.exact_lap_time_mismatch",
+        TimingIssue::ExactLapSumMismatch { .. } => "timing.exact_lap_sum_mismatch",
+        TimingIssue::ExactLapCountMismatch { .. } => "timing.exact_lap_count_mismatch",
+        TimingIssue::InputStreamLengthMismatch { .. } => "timing.input_stream_length_mismatch",
+        TimingIssue::InputFrameCountMismatch { .. } => "timing.input_frame_count_mismatch",
+    }
+}
+
+fn mii_code(issue: &MiiAuditIssue) -> &'static str {
+    match issue {
+        MiiAuditIssue::EmptyName => "mii.empty_name",
+        MiiAuditIssue::InvalidNameCharacter { .. } => "mii.invalid_name_character",
+        MiiAuditIssue::OutOfRange { .. } => "mii.out_of_range",
+        MiiAuditIssue::BirthdayDayWithoutMonth { .. } => "mii.birthday_day_without_month",
+        MiiAuditIssue::SpecialMiiMingles => "mii.special_mii_mingles",
+        MiiAuditIssue::ForeignMiiMingles => "mii.foreign_mii_mingles",
+        MiiAuditIssue::DownloadedButNotForeign => "mii.downloaded_but_not_foreign",
+        MiiAuditIssue::DownloadedFavorite => "mii.downloaded_favorite",
+    }
+}
+
+/// Everything the Mii Channel can't produce points at hand-edited data. An empty name is only
+/// informational, ghosts saved without a Mii have one.
+fn mii_severity(issue: &MiiAuditIssue) -> Severity {
+    match issue {
+        MiiAuditIssue::EmptyName => Severity::Info,
+        MiiAuditIssue::InvalidNameCharacter { .. }
+        | MiiAuditIssue::OutOfRange { .. }
+        | MiiAuditIssue::BirthdayDayWithoutMonth { .. }
+        | MiiAuditIssue::SpecialMiiMingles
+        | MiiAuditIssue::ForeignMiiMingles
+        | MiiAuditIssue::DownloadedButNotForeign
+        | MiiAuditIssue::DownloadedFavorite => Severity::Review,
+    }
+}
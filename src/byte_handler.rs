@@ -1,111 +1,86 @@
+use crate::layout::{BitField, LayoutError};
+
 #[derive(thiserror::Error, Debug)]
 pub enum ByteHandlerError {
     #[error("Couldn't convert type to ByteHandler: Too Long")]
     ConversionErrorTooLong,
 }
 
-#[repr(C)]
-#[derive(Clone, Copy)]
-pub(crate) union ByteHandler {
+/// Up to 4 bytes of big-endian data held as one number, with the first byte as its most
+/// significant one. Shorter data is padded with zeroes at the end, so byte 0 is always the
+/// first byte that was read, whatever the endianness of the machine. Writing goes through
+/// `crate::layout::BitField`, which works on the byte slices directly.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ByteHandler {
     dword: u32,
-    words: [u16; 2],
-    bytes: [u8; 4],
 }
 
 impl ByteHandler {
     pub const fn copy_dword(self) -> u32 {
-        unsafe { self.dword }
-    }
-
-    pub const fn copy_word(self, idx: usize) -> u16 {
-        if idx > 1 {
-            return 0;
-        }
-
-        #[cfg(target_endian = "little")]
-        let idx = 1 - idx;
-
-        unsafe { self.words[idx] }
+        self.dword
     }
 
+    /// Copies the `idx`th byte, 0 being the most significant one
     pub const fn copy_byte(self, idx: usize) -> u8 {
         if idx > 3 {
             return 0;
         }
 
-        #[cfg(target_endian = "little")]
-        let idx = 3 - idx;
-
-        unsafe { self.bytes[idx] }
+        (self.dword >> (8 * (3 - idx))) as u8
     }
 
     pub const fn shift_right(&mut self, d: u8) {
-        unsafe {
-            self.dword >>= d;
-        }
-    }
-
-    pub const fn shift_left(&mut self, d: u8) {
-        unsafe {
-            self.dword <<= d;
-        }
+        self.dword = match self.dword.checked_shr(d as u32) {
+            Some(dword) => dword,
+            None => 0,
+        };
     }
 
-    /// Reads the nth bit from the right counting from 0
+    /// Reads bit `d % 8` of byte `d / 8`, bits counted from the least significant one
     pub const fn read_bool(&self, d: u8) -> bool {
         if d >= 32 {
             return false;
         }
 
-        #[cfg(target_endian = "little")]
-        return (unsafe { self.dword } & (1u32 << ((d % 8) + 8 * (3 - (d / 8))))) != 0;
+        self.dword & Self::bool_mask(d) != 0
+    }
+
+    /// Reads a layout field out of the handled bytes, which start at byte `start` of the data
+    /// the field's offset is counted from
+    pub fn read_field(self, field: BitField, start: usize) -> Result<u32, LayoutError> {
+        field
+            .relative_to(start)
+            .read(&self.copy_dword().to_be_bytes())
+    }
 
-        #[cfg(target_endian = "big")]
-        return (unsafe { self.dword } & (1u32 << d)) != 0;
+    const fn bool_mask(d: u8) -> u32 {
+        1 << ((d % 8) as u32 + 8 * (3 - (d / 8) as u32))
     }
 }
 
 impl From<[u8; 4]> for ByteHandler {
     fn from(value: [u8; 4]) -> Self {
         ByteHandler {
-            #[cfg(target_endian = "big")]
-            bytes: [value[0], value[1], value[2], value[3]],
-            #[cfg(target_endian = "little")]
-            bytes: [value[3], value[2], value[1], value[0]],
+            dword: u32::from_be_bytes(value),
         }
     }
 }
 
 impl From<[u8; 3]> for ByteHandler {
     fn from(value: [u8; 3]) -> Self {
-        ByteHandler {
-            #[cfg(target_endian = "big")]
-            bytes: [value[0], value[1], value[2], 0],
-            #[cfg(target_endian = "little")]
-            bytes: [0, value[2], value[1], value[0]],
-        }
+        Self::from([value[0], value[1], value[2], 0])
     }
 }
 
 impl From<[u8; 2]> for ByteHandler {
     fn from(value: [u8; 2]) -> Self {
-        ByteHandler {
-            #[cfg(target_endian = "big")]
-            bytes: [value[0], value[1], 0, 0],
-            #[cfg(target_endian = "little")]
-            bytes: [0, 0, value[1], value[0]],
-        }
+        Self::from([value[0], value[1], 0, 0])
     }
 }
 
 impl From<u8> for ByteHandler {
     fn from(value: u8) -> Self {
-        ByteHandler {
-            #[cfg(target_endian = "big")]
-            bytes: [value, 0, 0, 0],
-            #[cfg(target_endian = "little")]
-            bytes: [0, 0, 0, value],
-        }
+        Self::from([value, 0, 0, 0])
     }
 }
 
@@ -118,45 +93,28 @@ impl From<u32> for ByteHandler {
 impl From<[u16; 2]> for ByteHandler {
     fn from(value: [u16; 2]) -> Self {
         ByteHandler {
-            #[cfg(target_endian = "big")]
-            words: [value[0], value[1]],
-            #[cfg(target_endian = "little")]
-            words: [value[1], value[0]],
+            dword: ((value[0] as u32) << 16) | value[1] as u32,
         }
     }
 }
 
 impl From<u16> for ByteHandler {
     fn from(value: u16) -> Self {
-        ByteHandler {
-            #[cfg(target_endian = "big")]
-            words: [value, 0],
-            #[cfg(target_endian = "little")]
-            words: [0, value],
-        }
+        Self::from([value, 0])
     }
 }
 
-macro_rules! shorten_syntax {
-    ($num:literal $value:ident $type:ty) => {
-        Ok(From::from(unsafe {
-            TryInto::<[$type; $num]>::try_into($value).unwrap_unchecked()
-        }))
-    };
-}
-
 impl TryFrom<&[u8]> for ByteHandler {
     type Error = ByteHandlerError;
 
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        match value.len() {
-            0 => Ok(From::from(0u32)),
-            1 => Ok(From::from(value[0])),
-            2 => shorten_syntax!(2 value u8),
-            3 => shorten_syntax!(3 value u8),
-            4 => shorten_syntax!(4 value u8),
-            _ => Err(ByteHandlerError::ConversionErrorTooLong),
+        if value.len() > 4 {
+            return Err(ByteHandlerError::ConversionErrorTooLong);
         }
+
+        let mut bytes = [0; 4];
+        bytes[..value.len()].copy_from_slice(value);
+        Ok(Self::from(bytes))
     }
 }
 
@@ -164,10 +122,10 @@ impl TryFrom<&[u16]> for ByteHandler {
     type Error = ByteHandlerError;
 
     fn try_from(value: &[u16]) -> Result<Self, Self::Error> {
-        match value.len() {
-            0 => Ok(From::from(0u32)),
-            1 => Ok(From::from(value[0])),
-            2 => shorten_syntax!(2 value u16),
+        match *value {
+            [] => Ok(From::from(0u32)),
+            [word] => Ok(From::from(word)),
+            [first, second] => Ok(From::from([first, second])),
             _ => Err(ByteHandlerError::ConversionErrorTooLong),
         }
     }
@@ -181,3 +139,24 @@ pub(crate) trait FromByteHandler: Sized {
         T: TryInto<ByteHandler>,
         Self::Err: From<T::Error>;
 }
+
+/// Decoding and encoding of a single packed field, e.g. a 3-byte `InGameTime` from a header or a
+/// personal best table. `decode` reads the field from the start of `bytes`, and `encode` writes it
+/// back there without touching bits that belong to neighbouring fields sharing the same bytes.
+pub trait FieldCodec: Sized {
+    /// Bytes the field spans
+    const SIZE: usize;
+    type Error;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error>;
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error>;
+}
+
+/// The first `T::SIZE` bytes, or an error naming `T` if there aren't enough
+pub(crate) fn field_bytes<T: FieldCodec>(bytes: &[u8]) -> Result<&[u8], LayoutError> {
+    bytes.get(..T::SIZE).ok_or(LayoutError::OutOfBounds {
+        field: core::any::type_name::<T>(),
+        end: T::SIZE,
+        length: bytes.len(),
+    })
+}
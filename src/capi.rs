@@ -0,0 +1,198 @@
+//! C ABI over `Ghost`. Ghosts are opaque `RkgGhost` handles from `rkg_ghost_parse`, released with
+//! `rkg_ghost_free`. The matching header is `capi/include/rkg_utils.h`, regenerate it with
+//! `cbindgen --config capi/cbindgen.toml --output capi/include/rkg_utils.h` after changing this file.
+
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::{
+    Ghost,
+    header::in_game_time::InGameTime,
+    input_data::{face_input::face_buttons_to_byte, input::Input},
+};
+
+/// A parsed ghost with its inputs already merged, so frames can be looked up quickly
+pub struct RkgGhost {
+    ghost: Ghost,
+    inputs: Vec<Input>,
+    /// First frame of every input in `inputs`
+    input_starts: Vec<u32>,
+}
+
+impl RkgGhost {
+    fn new(bytes: &[u8]) -> Option<Self> {
+        let ghost = Ghost::new(bytes).ok()?;
+
+        let inputs = ghost.input_data().inputs();
+        let input_starts = inputs
+            .iter()
+            .scan(0u32, |frame, input| {
+                let start = *frame;
+                *frame += input.frame_duration();
+                Some(start)
+            })
+            .collect();
+
+        Some(Self {
+            ghost,
+            inputs,
+            input_starts,
+        })
+    }
+
+    fn input_at(&self, frame: u32) -> Option<&Input> {
+        let index = self.input_starts.partition_point(|start| *start <= frame);
+        let input = self.inputs.get(index.checked_sub(1)?)?;
+
+        (frame < self.input_starts[index - 1] + input.frame_duration()).then_some(input)
+    }
+}
+
+/// A time as the game shows it, all zeroes if it couldn't be read
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RkgTime {
+    pub minutes: u8,
+    pub seconds: u8,
+    pub milliseconds: u16,
+}
+
+impl From<InGameTime> for RkgTime {
+    fn from(value: InGameTime) -> Self {
+        Self {
+            minutes: value.minutes(),
+            seconds: value.seconds(),
+            milliseconds: value.milliseconds(),
+        }
+    }
+}
+
+/// Controller state during a single frame
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RkgFrame {
+    /// Face button bits as stored in the file: 0x01 accelerator, 0x02 brake, 0x04 item,
+    /// 0x08 drift (always with the brake)
+    pub buttons: u8,
+    /// -7 to 7
+    pub stick_x: i8,
+    /// -7 to 7
+    pub stick_y: i8,
+    /// 0 none, 1 up, 2 down, 3 left, 4 right
+    pub dpad: u8,
+}
+
+impl From<&Input> for RkgFrame {
+    fn from(value: &Input) -> Self {
+        Self {
+            buttons: face_buttons_to_byte(value.face_buttons()),
+            stick_x: value.stick_x(),
+            stick_y: value.stick_y(),
+            dpad: value.dpad_button().into(),
+        }
+    }
+}
+
+/// Parses a whole ghost file. Returns NULL if the data isn't a valid ghost.
+///
+/// # Safety
+/// `data` must point to `length` readable bytes, which are only used during the call.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rkg_ghost_parse(data: *const u8, length: usize) -> *mut RkgGhost {
+    if data.is_null() {
+        return core::ptr::null_mut();
+    }
+
+    // SAFETY: checked for NULL, the caller guarantees the length
+    let bytes = unsafe { core::slice::from_raw_parts(data, length) };
+    // A panic can't unwind into C, it would abort the host. Treat one as invalid data.
+    std::panic::catch_unwind(|| RkgGhost::new(bytes))
+        .ok()
+        .flatten()
+        .map_or(core::ptr::null_mut(), |ghost| {
+            Box::into_raw(Box::new(ghost))
+        })
+}
+
+/// Releases a ghost from `rkg_ghost_parse`. Does nothing with NULL.
+///
+/// # Safety
+/// `ghost` must come from `rkg_ghost_parse` and not have been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rkg_ghost_free(ghost: *mut RkgGhost) {
+    if !ghost.is_null() {
+        // SAFETY: the caller guarantees the handle is live and owned by us
+        drop(unsafe { Box::from_raw(ghost) });
+    }
+}
+
+/// # Safety
+/// `ghost` must be NULL or a live handle from `rkg_ghost_parse`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rkg_ghost_finish_time(ghost: *const RkgGhost) -> RkgTime {
+    // SAFETY: the caller guarantees the handle is NULL or live
+    unsafe { ghost.as_ref() }
+        .map(|ghost| RkgTime::from(*ghost.ghost.header().finish_time()))
+        .unwrap_or_default()
+}
+
+/// # Safety
+/// `ghost` must be NULL or a live handle from `rkg_ghost_parse`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rkg_ghost_lap_count(ghost: *const RkgGhost) -> u8 {
+    // SAFETY: the caller guarantees the handle is NULL or live
+    unsafe { ghost.as_ref() }
+        .map(|ghost| ghost.ghost.header().lap_count())
+        .unwrap_or_default()
+}
+
+/// Split time of lap `lap`, counting from 0. All zeroes past the last lap.
+///
+/// # Safety
+/// `ghost` must be NULL or a live handle from `rkg_ghost_parse`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rkg_ghost_lap_time(ghost: *const RkgGhost, lap: u8) -> RkgTime {
+    // SAFETY: the caller guarantees the handle is NULL or live
+    unsafe { ghost.as_ref() }
+        .filter(|ghost| lap < ghost.ghost.header().lap_count())
+        .and_then(|ghost| ghost.ghost.header().lap_split_times().get(lap as usize))
+        .map(|time| RkgTime::from(*time))
+        .unwrap_or_default()
+}
+
+/// Amount of frames of input, 0 for NULL
+///
+/// # Safety
+/// `ghost` must be NULL or a live handle from `rkg_ghost_parse`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rkg_ghost_frame_count(ghost: *const RkgGhost) -> u32 {
+    // SAFETY: the caller guarantees the handle is NULL or live
+    unsafe { ghost.as_ref() }
+        .map(|ghost| ghost.ghost.input_data().frame_count())
+        .unwrap_or_default()
+}
+
+/// Writes the controller state of `frame` to `out`. Frames are 0-based, so valid ones are
+/// `frame < rkg_ghost_frame_count(ghost)`. Returns false, leaving `out` untouched, for any other
+/// frame or if a pointer is NULL.
+///
+/// # Safety
+/// `ghost` must be NULL or a live handle from `rkg_ghost_parse`, `out` NULL or writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rkg_ghost_frame_at(
+    ghost: *const RkgGhost,
+    frame: u32,
+    out: *mut RkgFrame,
+) -> bool {
+    // SAFETY: the caller guarantees both pointers are NULL or valid
+    let (Some(ghost), Some(out)) = (unsafe { ghost.as_ref() }, unsafe { out.as_mut() }) else {
+        return false;
+    };
+
+    match ghost.input_at(frame) {
+        Some(input) => {
+            *out = RkgFrame::from(input);
+            true
+        }
+        None => false,
+    }
+}
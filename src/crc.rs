@@ -0,0 +1,36 @@
+pub(crate) fn crc16(value: &[u8]) -> u16 {
+    let mut crc: u16 = 0x0000; // Initial value for XModem variant
+    let polynomial: u16 = 0x1021; // Standard CCITT polynomial
+
+    for &byte in value.iter() {
+        crc ^= (byte as u16) << 8; // XOR current byte with the high byte of CRC
+
+        for _ in 0..8 {
+            if crc & 0x8000 != 0 {
+                crc = (crc << 1) ^ polynomial;
+            } else {
+                crc <<= 1;
+            }
+        }
+    }
+    crc
+}
+
+/// Standard CRC-32 (IEEE 802.3), used at the end of RKG files and in save files
+pub(crate) fn crc32(value: &[u8]) -> u32 {
+    let mut crc: u32 = 0xFFFFFFFF;
+    let polynomial: u32 = 0xEDB88320; // Reversed 0x04C11DB7
+
+    for &byte in value.iter() {
+        crc ^= byte as u32;
+
+        for _ in 0..8 {
+            if crc & 0x01 != 0 {
+                crc = (crc >> 1) ^ polynomial;
+            } else {
+                crc >>= 1;
+            }
+        }
+    }
+    !crc
+}
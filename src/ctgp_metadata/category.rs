@@ -4,7 +4,7 @@ pub enum CategoryError {
     NonexistentCategory,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Category {
     NoShortcut,
     Shortcut,
@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use core::fmt::Display;
 
 #[derive(Clone, Copy, Debug)]
 pub struct CTGPVersion {
@@ -23,7 +23,7 @@ impl CTGPVersion {
 }
 
 impl Display for CTGPVersion {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "{:02}.{:02}.{:04}",
@@ -1,6 +1,33 @@
-use std::fmt::Display;
+use core::{
+    cmp::Ordering,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    iter::Sum,
+    ops::{Add, Sub},
+    str::FromStr,
+    time::Duration,
+};
 
-#[derive(Default, Clone, Copy)]
+use crate::header::in_game_time::{InGameTime, InGameTimeError};
+
+const PICOSECONDS_PER_SECOND: u64 = 1_000_000_000_000;
+const PICOSECONDS_PER_MINUTE: u64 = 60 * PICOSECONDS_PER_SECOND;
+const PICOSECONDS_PER_MILLISECOND: u64 = 1_000_000_000;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ExactFinishTimeError {
+    #[error("Minutes are invalid")]
+    MinutesInvalid,
+    #[error("Seconds are invalid")]
+    SecondsInvalid,
+    #[error("Time is not formatted as MM:SS.xxxxxxxxxxxx")]
+    InvalidFormat,
+}
+
+/// Finish or lap time with the sub-millisecond precision recovered from the CTGP footer.
+/// `picoseconds` is the fractional part of the second; the value CTGP stores can spill
+/// slightly past a whole second (or below zero), so comparisons go through `total_picoseconds`.
+#[derive(Default, Clone, Copy, Debug)]
 pub struct ExactFinishTime {
     minutes: u8,
     seconds: u8,
@@ -8,6 +35,13 @@ pub struct ExactFinishTime {
 }
 
 impl ExactFinishTime {
+    /// Longest time that `from_picoseconds` accepts, 255:59.999999999999
+    pub const MAX: Self = Self {
+        minutes: u8::MAX,
+        seconds: 59,
+        picoseconds: PICOSECONDS_PER_SECOND - 1,
+    };
+
     #[inline(always)]
     pub fn new(minutes: u8, seconds: u8, picoseconds: u64) -> Self {
         Self {
@@ -17,6 +51,17 @@ impl ExactFinishTime {
         }
     }
 
+    pub fn from_picoseconds(picoseconds: u64) -> Result<Self, ExactFinishTimeError> {
+        let minutes = u8::try_from(picoseconds / PICOSECONDS_PER_MINUTE)
+            .map_err(|_| ExactFinishTimeError::MinutesInvalid)?;
+
+        Ok(Self::new(
+            minutes,
+            ((picoseconds / PICOSECONDS_PER_SECOND) % 60) as u8,
+            picoseconds % PICOSECONDS_PER_SECOND,
+        ))
+    }
+
     pub fn minutes(self) -> u8 {
         self.minutes
     }
@@ -28,14 +73,196 @@ impl ExactFinishTime {
     pub fn picoseconds(self) -> u64 {
         self.picoseconds
     }
+
+    pub fn total_picoseconds(self) -> u64 {
+        // Wrapping so a slightly negative fractional part (stored as two's complement) still works
+        (self.minutes as u64 * PICOSECONDS_PER_MINUTE
+            + self.seconds as u64 * PICOSECONDS_PER_SECOND)
+            .wrapping_add(self.picoseconds)
+    }
+
+    pub fn total_nanoseconds(self) -> u64 {
+        self.total_picoseconds() / 1000
+    }
+
+    /// Returns the time the game itself displays for this exact time. The game rounds any
+    /// leftover fraction up to the next millisecond rather than to nearest, e.g.
+    /// 00:58.647988872949 is shown as 00:58.648 and 01:44.442094... as 01:44.443.
+    pub fn to_in_game_time(self) -> Result<InGameTime, InGameTimeError> {
+        let millis = u32::try_from(
+            self.total_picoseconds()
+                .div_ceil(PICOSECONDS_PER_MILLISECOND),
+        )
+        .map_err(|_| InGameTimeError::MinutesInvalid)?;
+        InGameTime::from_millis(millis)
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Self::from_picoseconds(
+            self.total_picoseconds()
+                .checked_add(rhs.total_picoseconds())?,
+        )
+        .ok()
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Self::from_picoseconds(
+            self.total_picoseconds()
+                .checked_sub(rhs.total_picoseconds())?,
+        )
+        .ok()
+    }
+
+    /// Like `checked_add`, but stops at `ExactFinishTime::MAX`
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).unwrap_or(Self::MAX)
+    }
+
+    /// Like `checked_sub`, but stops at zero
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        match self
+            .total_picoseconds()
+            .checked_sub(rhs.total_picoseconds())
+        {
+            Some(picoseconds) => Self::saturating_from_picoseconds(picoseconds),
+            None => Self::default(),
+        }
+    }
+
+    /// Absolute difference between two times, e.g. to find the gap between two tied ghosts.
+    /// Returns `ExactFinishTime::MAX` if they're further apart than that.
+    pub fn abs_diff(self, other: Self) -> Self {
+        Self::saturating_from_picoseconds(
+            self.total_picoseconds().abs_diff(other.total_picoseconds()),
+        )
+    }
+
+    fn saturating_from_picoseconds(picoseconds: u64) -> Self {
+        Self::from_picoseconds(picoseconds).unwrap_or(Self::MAX)
+    }
 }
 
 impl Display for ExactFinishTime {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let total = self.total_picoseconds();
         write!(
             f,
             "{:02}:{:02}.{:012}",
-            self.minutes, self.seconds, self.picoseconds
+            total / PICOSECONDS_PER_MINUTE,
+            (total / PICOSECONDS_PER_SECOND) % 60,
+            total % PICOSECONDS_PER_SECOND
         )
     }
 }
+
+impl FromStr for ExactFinishTime {
+    type Err = ExactFinishTimeError;
+
+    /// Parses the `Display` form, `MM:SS.xxxxxxxxxxxx`. A single minute digit is accepted too
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (minutes, rest) = s
+            .split_once(':')
+            .ok_or(ExactFinishTimeError::InvalidFormat)?;
+        let (seconds, picoseconds) = rest
+            .split_once('.')
+            .ok_or(ExactFinishTimeError::InvalidFormat)?;
+
+        if !(1..=2).contains(&minutes.len()) || seconds.len() != 2 || picoseconds.len() != 12 {
+            return Err(ExactFinishTimeError::InvalidFormat);
+        }
+        let parse = |digits: &str| {
+            if !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(ExactFinishTimeError::InvalidFormat);
+            }
+            digits
+                .parse::<u64>()
+                .map_err(|_| ExactFinishTimeError::InvalidFormat)
+        };
+
+        let seconds = parse(seconds)?;
+        if seconds > 59 {
+            return Err(ExactFinishTimeError::SecondsInvalid);
+        }
+
+        Ok(Self::new(
+            parse(minutes)? as u8,
+            seconds as u8,
+            parse(picoseconds)?,
+        ))
+    }
+}
+
+impl PartialEq for ExactFinishTime {
+    fn eq(&self, other: &Self) -> bool {
+        self.total_picoseconds() == other.total_picoseconds()
+    }
+}
+
+impl Eq for ExactFinishTime {}
+
+impl Hash for ExactFinishTime {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.total_picoseconds().hash(state);
+    }
+}
+
+impl PartialOrd for ExactFinishTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ExactFinishTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.total_picoseconds().cmp(&other.total_picoseconds())
+    }
+}
+
+impl Add for ExactFinishTime {
+    type Output = Self;
+
+    /// Panics if the result is longer than `ExactFinishTime::MAX`, see `checked_add` and `saturating_add`
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs).expect("attempt to add with overflow")
+    }
+}
+
+impl Sub for ExactFinishTime {
+    type Output = Self;
+
+    /// Panics if `rhs` is longer than `self`, see `checked_sub`, `saturating_sub` and `abs_diff`
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl Sum for ExactFinishTime {
+    /// Panics on overflow like `Add`
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a ExactFinishTime> for ExactFinishTime {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl From<InGameTime> for ExactFinishTime {
+    fn from(value: InGameTime) -> Self {
+        Self::new(
+            value.minutes(),
+            value.seconds(),
+            value.milliseconds() as u64 * PICOSECONDS_PER_MILLISECOND,
+        )
+    }
+}
+
+impl From<ExactFinishTime> for Duration {
+    /// Sub-nanosecond precision is lost
+    fn from(value: ExactFinishTime) -> Self {
+        Duration::from_nanos(value.total_nanoseconds())
+    }
+}
@@ -2,27 +2,39 @@ use crate::byte_handler::FromByteHandler;
 use crate::ctgp_metadata::exact_finish_time::ExactFinishTime;
 use crate::ctgp_metadata::{category::Category, ctgp_version::CTGPVersion};
 use crate::header::in_game_time::InGameTime;
-use crate::{byte_handler::ByteHandler, input_data::yaz1_decompress};
+use crate::timing::race_clock::RaceClock;
+use crate::{byte_handler::ByteHandler, input_data::InputData};
+use alloc::vec::Vec;
 use chrono::{Duration, TimeDelta, prelude::*};
 
 pub mod category;
 pub mod ctgp_version;
 pub mod exact_finish_time;
 
+/// Bytes of every field after the security data, up to the final flags byte
+const FIELDS_SIZE: usize = 0x7F;
+
 #[derive(thiserror::Error, Debug)]
 pub enum CTGPMetadataError {
     #[error("Ghost is not CKGD")]
     NotCKGD,
     #[error("Invalid CTGP metadata version")]
     InvalidMetadataVersion,
+    #[error("Data is too short to hold a CTGP footer")]
+    TooShort,
+    #[error("CTGP footer size {0:#X} doesn't fit the data")]
+    InvalidMetadataSize(u32),
+    #[error("Input Data Error: {0}")]
+    InputDataError(#[from] crate::input_data::InputDataError),
     #[error("Try From Slice Error: {0}")]
-    TryFromSliceError(#[from] std::array::TryFromSliceError),
+    TryFromSliceError(#[from] core::array::TryFromSliceError),
     #[error("Category Error: {0}")]
     CategoryError(#[from] category::CategoryError),
     #[error("In Game Time Error")]
     InGameTimeError(#[from] crate::header::in_game_time::InGameTimeError),
 }
 
+#[derive(Clone)]
 pub struct CTGPMetadata {
     security_data: Vec<u8>,
     track_sha1: [u8; 0x14],
@@ -57,6 +69,10 @@ pub struct CTGPMetadata {
 impl CTGPMetadata {
     /// Expects full rkg data
     pub fn new(data: &[u8]) -> Result<Self, CTGPMetadataError> {
+        // Header, then the footer's version, size, magic and CRC32
+        if data.len() < 0x88 + 0x0D {
+            return Err(CTGPMetadataError::TooShort);
+        }
         if data[data.len() - 0x08..data.len() - 0x04] != [0x43, 0x4B, 0x47, 0x44] {
             return Err(CTGPMetadataError::NotCKGD);
         }
@@ -67,9 +83,19 @@ impl CTGPMetadata {
         let metadata_version = data[data.len() - 0x0D];
         let security_data_size = if metadata_version < 7 { 0x44 } else { 0x54 };
 
+        // The footer has to fit after the header and hold every field read below
+        let metadata_start = data
+            .len()
+            .checked_sub(metadata_size as usize)
+            .filter(|start| *start >= 0x88)
+            .ok_or(CTGPMetadataError::InvalidMetadataSize(metadata_size))?;
+        if (metadata_size as usize) < security_data_size + FIELDS_SIZE {
+            return Err(CTGPMetadataError::InvalidMetadataSize(metadata_size));
+        }
+
         let header_data = &data[..0x88];
-        let input_data = &data[0x88..data.len() - metadata_size as usize];
-        let metadata = &data[data.len() - metadata_size as usize..];
+        let input_data = &data[0x88..metadata_start];
+        let metadata = &data[metadata_start..];
         let mut current_offset = 0usize;
 
         let security_data = Vec::from(&metadata[..security_data_size]);
@@ -90,14 +116,15 @@ impl CTGPMetadata {
         current_offset += 0x08;
 
         let finish_time = InGameTime::from_byte_handler(&header_data[0x04..0x07])?;
-        let true_time_subtraction =
-            (f32::from_be_bytes(metadata[current_offset..current_offset + 0x04].try_into()?) as f64
-                * 1e+9)
-                .floor() as i64;
+        let true_time_subtraction = floor_to_i64(
+            f32::from_be_bytes(metadata[current_offset..current_offset + 0x04].try_into()?) as f64
+                * 1e+9,
+        );
         let exact_finish_time = ExactFinishTime::new(
             finish_time.minutes(),
             finish_time.seconds(),
-            (finish_time.milliseconds() as i64 * 1e+9 as i64 + true_time_subtraction) as u64,
+            (finish_time.milliseconds() as i64 * 1e+9 as i64).saturating_add(true_time_subtraction)
+                as u64,
         );
         current_offset += 0x04;
 
@@ -129,34 +156,37 @@ impl CTGPMetadata {
         // Exact lap split calculation
         let mut previous_subtractions = 0i64;
         let mut exact_lap_times = [ExactFinishTime::default(); 10];
-        let lap_count = header_data[0x10];
+        // Only 10 laps are stored, the header is checked separately
+        let lap_count = header_data[0x10].min(10);
         let mut in_game_time_offset = 0x11usize;
         let mut subtraction_ps = 0i64;
 
-        for index in 0..lap_count as usize {
-            let mut true_time_subtraction =
-                ((f32::from_be_bytes(metadata[current_offset..current_offset + 0x04].try_into()?)
-                    as f64)
-                    * 1e+9)
-                    .floor() as i64;
+        for exact_lap_time in exact_lap_times.iter_mut().take(lap_count as usize) {
+            let mut true_time_subtraction = floor_to_i64(
+                f32::from_be_bytes(metadata[current_offset..current_offset + 0x04].try_into()?)
+                    as f64
+                    * 1e+9,
+            );
 
             let lap_time = InGameTime::from_byte_handler(
                 &header_data[in_game_time_offset..in_game_time_offset + 0x03],
             )?;
 
             // subtract the sum of the previous laps' difference because the lap differences add up to
-            // have its decimal portion be equal to the total time
-            true_time_subtraction -= previous_subtractions;
+            // have its decimal portion be equal to the total time. Saturating, the floats can be
+            // anything in a corrupt footer
+            true_time_subtraction = true_time_subtraction.saturating_sub(previous_subtractions);
 
             if true_time_subtraction > 1e+9 as i64 {
                 true_time_subtraction -= subtraction_ps;
                 subtraction_ps = if subtraction_ps == 0 { 1e+9 as i64 } else { 0 };
             }
-            previous_subtractions += true_time_subtraction;
-            exact_lap_times[index] = ExactFinishTime::new(
+            previous_subtractions = previous_subtractions.saturating_add(true_time_subtraction);
+            *exact_lap_time = ExactFinishTime::new(
                 lap_time.minutes(),
                 lap_time.seconds(),
-                (lap_time.milliseconds() as i64 * 1e+9 as i64 + true_time_subtraction) as u64,
+                (lap_time.milliseconds() as i64 * 1e+9 as i64).saturating_add(true_time_subtraction)
+                    as u64,
             );
             in_game_time_offset += 0x03;
             current_offset -= 0x04;
@@ -181,27 +211,17 @@ impl CTGPMetadata {
 
         // Pause frame times
         let mut pause_frames = Vec::new();
-        let input_data = if input_data[4..8] == [0x59, 0x61, 0x7A, 0x31] {
-            // YAZ1 header, decompress
-            yaz1_decompress(&input_data[4..]).unwrap()
-        } else {
-            Vec::from(input_data)
-        };
-
-        let face_input_count = u16::from_be_bytes([input_data[0], input_data[1]]);
+        let input_data = InputData::new(input_data)?;
+        let raw_input_data = input_data.raw_input_data();
+        let face_input_count = u16::from_be_bytes([raw_input_data[0], raw_input_data[1]]);
 
-        let mut current_input_byte = 8;
         let mut elapsed_frames = 1u32;
-        while current_input_byte < 8 + face_input_count * 2 {
-            let idx = current_input_byte as usize;
-            let input = &input_data[idx..idx + 2];
-
+        for input in raw_input_data[8..8 + face_input_count as usize * 2].chunks_exact(2) {
             if contains_ctgp_pause(input[0]) {
                 pause_frames.push(elapsed_frames);
             }
 
             elapsed_frames += input[1] as u32;
-            current_input_byte += 2;
         }
 
         let bool_handler = ByteHandler::from(metadata[current_offset]);
@@ -214,8 +234,11 @@ impl CTGPMetadata {
         let mut shroomstrat: [u8; 10] = [0; 10];
         for _ in 0..3 {
             let lap = metadata[current_offset];
-            if lap != 0 {
-                shroomstrat[(lap - 1) as usize] += 1;
+            if let Some(count) = lap
+                .checked_sub(1)
+                .and_then(|lap| shroomstrat.get_mut(lap as usize))
+            {
+                *count += 1;
             }
             current_offset += 0x01;
         }
@@ -307,10 +330,20 @@ impl CTGPMetadata {
         self.rtc_time_paused
     }
 
+    /// Frames (counted from 1 at the first frame of input) on which a pause was pressed
     pub fn pause_frames(&self) -> &Vec<u32> {
         &self.pause_frames
     }
 
+    /// Race timer at each pause, None for pauses during the countdown
+    pub fn pause_times(&self) -> Vec<Option<ExactFinishTime>> {
+        let race_clock = RaceClock::default();
+        self.pause_frames
+            .iter()
+            .map(|frame| race_clock.frame_to_time(*frame))
+            .collect()
+    }
+
     pub fn my_stuff_enabled(&self) -> bool {
         self.my_stuff_enabled
     }
@@ -388,6 +421,16 @@ fn datetime_from_timestamp(tick_count: u64) -> NaiveDateTime {
     epoch.naive_utc() + duration
 }
 
+/// `f64::floor` needs `std`, the float is cast the same way afterwards
+fn floor_to_i64(value: f64) -> i64 {
+    let truncated = value as i64;
+    if truncated as f64 > value {
+        truncated.saturating_sub(1)
+    } else {
+        truncated
+    }
+}
+
 fn duration_from_ticks(tick_count: u64) -> TimeDelta {
     let clock_rate = 60_750_000.0; // 60.75 MHz tick speed
     let total_seconds = tick_count as f64 / clock_rate;
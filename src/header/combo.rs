@@ -1,6 +1,12 @@
-use crate::byte_handler::{ByteHandler, ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandler, ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::{layout, mii::Mii},
+    layout::LayoutError,
+};
+use alloc::vec::Vec;
 
 /// Struct that handles the validity of the Character/Vehicle combo used in the RKG file
+#[derive(Clone, Copy)]
 pub struct Combo {
     character: Character,
     vehicle: Vehicle,
@@ -20,16 +26,118 @@ pub enum ComboError {
     ImpossibleCharacterId,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
+}
+
+/// How strictly combos are checked when they are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ComboValidation {
+    /// Impossible characters and mismatched weight classes are errors
+    #[default]
+    Strict,
+    /// Everything is read, problems are kept as `ComboIssue`s
+    Warn,
+    /// Everything is read and nothing is checked
+    Permissive,
+}
+
+/// A combo the game could never have produced
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComboIssue {
+    /// Character exists in the game but can't be selected, see `Character::is_impossible`
+    ImpossibleCharacter(Character),
+    IncongruentWeightClasses {
+        character: Character,
+        vehicle: Vehicle,
+    },
+    /// Mii outfit is for the other gender than the Mii driving it
+    MiiOutfitMismatch { character: Character, is_girl: bool },
+}
+
+impl core::fmt::Display for ComboIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::ImpossibleCharacter(character) => {
+                write!(f, "{character:?} can't be selected in time trials")
+            }
+            Self::IncongruentWeightClasses { character, vehicle } => {
+                write!(f, "{character:?} can't drive {vehicle:?}")
+            }
+            Self::MiiOutfitMismatch { character, is_girl } => write!(
+                f,
+                "{character:?} is driven by a {} Mii",
+                if *is_girl { "female" } else { "male" }
+            ),
+        }
+    }
 }
 
 impl Combo {
+    /// Only creates combos that can be selected in game
     #[inline(always)]
     pub fn new(vehicle: Vehicle, character: Character) -> Result<Self, ComboError> {
-        if character.get_weight_class() != vehicle.get_weight_class() {
-            return Err(ComboError::IncongruentWeightClasses);
+        Self::new_with_validation(vehicle, character, ComboValidation::Strict)
+    }
+
+    /// Creates a combo without any checks, e.g. to reproduce a modified ghost
+    pub fn new_unchecked(vehicle: Vehicle, character: Character) -> Self {
+        Self { vehicle, character }
+    }
+
+    pub fn new_with_validation(
+        vehicle: Vehicle,
+        character: Character,
+        validation: ComboValidation,
+    ) -> Result<Self, ComboError> {
+        let combo = Self::new_unchecked(vehicle, character);
+
+        if validation == ComboValidation::Strict {
+            match combo.issues().first() {
+                Some(ComboIssue::ImpossibleCharacter(_)) => {
+                    return Err(ComboError::ImpossibleCharacterId);
+                }
+                Some(ComboIssue::IncongruentWeightClasses { .. }) => {
+                    return Err(ComboError::IncongruentWeightClasses);
+                }
+                _ => (),
+            }
+        }
+
+        Ok(combo)
+    }
+
+    /// Returns every reason the game couldn't have produced this combo
+    pub fn issues(&self) -> Vec<ComboIssue> {
+        let mut issues = Vec::new();
+
+        if self.character.is_impossible() {
+            issues.push(ComboIssue::ImpossibleCharacter(self.character));
+        }
+        if self.character.get_weight_class() != self.vehicle.get_weight_class() {
+            issues.push(ComboIssue::IncongruentWeightClasses {
+                character: self.character,
+                vehicle: self.vehicle,
+            });
         }
 
-        Ok(Self { vehicle, character })
+        issues
+    }
+
+    /// Like `issues`, but also checks the outfit against the gender of the Mii driving it
+    pub fn issues_with_mii(&self, mii: &Mii) -> Vec<ComboIssue> {
+        let mut issues = self.issues();
+
+        if let Some(is_female_outfit) = self.character.is_female_mii_outfit()
+            && is_female_outfit != mii.is_girl()
+        {
+            issues.push(ComboIssue::MiiOutfitMismatch {
+                character: self.character,
+                is_girl: mii.is_girl(),
+            });
+        }
+
+        issues
     }
 
     pub fn character(&self) -> Character {
@@ -51,13 +159,7 @@ impl FromByteHandler for Combo {
         T: TryInto<ByteHandler>,
         Self::Err: From<T::Error>,
     {
-        let mut handler = handler.try_into()?;
-
-        handler.shift_right(2); // 1. 00VVVVVV
-        let vehicle = handler.copy_byte(0);
-
-        handler.shift_right(2); // 2. VVCCCCCC
-        let character = handler.copy_byte(1) & 0x3F;
+        let (vehicle, character) = read_ids(handler.try_into()?);
 
         Self::new(
             Vehicle::try_from(vehicle).map_err(|_| ComboError::InvalidVehicleId)?,
@@ -66,6 +168,17 @@ impl FromByteHandler for Combo {
     }
 }
 
+/// Returns vehicle and character ID
+fn read_ids(mut handler: ByteHandler) -> (u8, u8) {
+    handler.shift_right(2); // 1. 00VVVVVV
+    let vehicle = handler.copy_byte(0);
+
+    handler.shift_right(2); // 2. VVCCCCCC
+    let character = handler.copy_byte(1) & 0x3F;
+
+    (vehicle, character)
+}
+
 impl GetWeightClass for Combo {
     fn get_weight_class(&self) -> WeightClass {
         self.character.get_weight_class()
@@ -138,7 +251,34 @@ pub enum Character {
 }
 
 impl Character {
-    fn is_impossible(self) -> bool {
+    /// Returns Some(true) for female Mii outfits, Some(false) for male ones and None for everything else
+    pub fn is_female_mii_outfit(self) -> Option<bool> {
+        match self {
+            Self::SmallMiiOutfitAFemale
+            | Self::SmallMiiOutfitBFemale
+            | Self::SmallMiiOutfitCFemale
+            | Self::MediumMiiOutfitAFemale
+            | Self::MediumMiiOutfitBFemale
+            | Self::MediumMiiOutfitCFemale
+            | Self::LargeMiiOutfitAFemale
+            | Self::LargeMiiOutfitBFemale
+            | Self::LargeMiiOutfitCFemale => Some(true),
+            Self::SmallMiiOutfitAMale
+            | Self::SmallMiiOutfitBMale
+            | Self::SmallMiiOutfitCMale
+            | Self::MediumMiiOutfitAMale
+            | Self::MediumMiiOutfitBMale
+            | Self::MediumMiiOutfitCMale
+            | Self::LargeMiiOutfitAMale
+            | Self::LargeMiiOutfitBMale
+            | Self::LargeMiiOutfitCMale => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Returns true for characters that exist in the game but can't be selected in time trials,
+    /// i.e. the menu versions of Peach, Daisy and Rosalina and Miis without an outfit
+    pub fn is_impossible(self) -> bool {
         match self {
             Self::Mario
             | Self::BabyPeach
@@ -530,3 +670,22 @@ impl GetWeightClass for Vehicle {
         }
     }
 }
+
+impl FieldCodec for Combo {
+    const SIZE: usize = 2;
+    type Error = ComboError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::VEHICLE
+            .relative_to(0x08)
+            .write(bytes, u8::from(self.vehicle) as u32)?;
+        layout::CHARACTER
+            .relative_to(0x08)
+            .write(bytes, u8::from(self.character) as u32)?;
+        Ok(())
+    }
+}
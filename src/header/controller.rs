@@ -1,6 +1,10 @@
-use std::convert::Infallible;
+use core::convert::Infallible;
 
-use crate::byte_handler::{ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::layout,
+    layout::LayoutError,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum ControllerError {
@@ -8,6 +12,8 @@ pub enum ControllerError {
     NonexistentControllerID,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
     #[error("")]
     Infallible(#[from] Infallible),
 }
@@ -55,3 +61,19 @@ impl From<Controller> for u8 {
         }
     }
 }
+
+impl FieldCodec for Controller {
+    const SIZE: usize = 1;
+    type Error = ControllerError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::CONTROLLER
+            .relative_to(0x0B)
+            .write(bytes, u8::from(*self) as u32)?;
+        Ok(())
+    }
+}
@@ -1,4 +1,8 @@
-use crate::byte_handler::{ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::layout,
+    layout::LayoutError,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum DateError {
@@ -10,9 +14,11 @@ pub enum DateError {
     DayInvalid,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Date {
     year: u8,
     month: u8,
@@ -79,3 +85,25 @@ impl PartialEq for Date {
         self.day == other.day && self.month == other.month && self.year == other.year
     }
 }
+
+impl FieldCodec for Date {
+    const SIZE: usize = 3;
+    type Error = DateError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::YEAR
+            .relative_to(0x09)
+            .write(bytes, self.year as u32)?;
+        layout::MONTH
+            .relative_to(0x09)
+            .write(bytes, self.month as u32)?;
+        layout::DAY
+            .relative_to(0x09)
+            .write(bytes, self.day as u32)?;
+        Ok(())
+    }
+}
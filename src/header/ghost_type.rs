@@ -1,11 +1,19 @@
-use crate::byte_handler::{ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::layout,
+    layout::LayoutError,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum GhostTypeError {
     #[error("Nonexistent Ghost Type")]
     NonexistentGhostType,
+    #[error("Friend Index Out Of Range (0..=29)")]
+    FriendIndexOutOfRange,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -16,7 +24,8 @@ pub enum GhostType {
     Rival,
     Special,
     GhostRace,
-    Friend,
+    /// Friend ghost, holding which of the 30 friend slots (0..=29) it belongs to
+    Friend(u8),
     NormalStaff,
     ExpertStaff,
 }
@@ -31,7 +40,7 @@ impl TryFrom<u8> for GhostType {
             0x04 => Ok(Self::Rival),
             0x05 => Ok(Self::Special),
             0x06 => Ok(Self::GhostRace),
-            0x07..=0x24 => Ok(Self::Friend),
+            0x07..=0x24 => Ok(Self::Friend(value - 0x07)),
             0x25 => Ok(Self::NormalStaff),
             0x26 => Ok(Self::ExpertStaff),
             _ => Err(GhostTypeError::NonexistentGhostType),
@@ -39,18 +48,20 @@ impl TryFrom<u8> for GhostType {
     }
 }
 
-impl From<GhostType> for u8 {
-    fn from(value: GhostType) -> Self {
+impl TryFrom<GhostType> for u8 {
+    type Error = GhostTypeError;
+    fn try_from(value: GhostType) -> Result<Self, Self::Error> {
         match value {
-            GhostType::PlayerBest => 0x01,
-            GhostType::WorldRecord => 0x02,
-            GhostType::ContinentalRecord => 0x03,
-            GhostType::Rival => 0x04,
-            GhostType::Special => 0x05,
-            GhostType::GhostRace => 0x06,
-            GhostType::Friend => 0x24,
-            GhostType::NormalStaff => 0x25,
-            GhostType::ExpertStaff => 0x26,
+            GhostType::PlayerBest => Ok(0x01),
+            GhostType::WorldRecord => Ok(0x02),
+            GhostType::ContinentalRecord => Ok(0x03),
+            GhostType::Rival => Ok(0x04),
+            GhostType::Special => Ok(0x05),
+            GhostType::GhostRace => Ok(0x06),
+            GhostType::Friend(index @ 0..=0x1D) => Ok(0x07 + index),
+            GhostType::Friend(_) => Err(GhostTypeError::FriendIndexOutOfRange),
+            GhostType::NormalStaff => Ok(0x25),
+            GhostType::ExpertStaff => Ok(0x26),
         }
     }
 }
@@ -65,6 +76,23 @@ impl FromByteHandler for GhostType {
     {
         let mut handler = handler.try_into()?;
         handler.shift_right(2);
-        handler.copy_byte(1).try_into()
+        // Ghost type is 7 bits, the top bit of this byte is unknown
+        (handler.copy_byte(1) & 0x7F).try_into()
+    }
+}
+
+impl FieldCodec for GhostType {
+    const SIZE: usize = 2;
+    type Error = GhostTypeError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::GHOST_TYPE
+            .relative_to(0x0C)
+            .write(bytes, u8::try_from(*self)? as u32)?;
+        Ok(())
     }
 }
@@ -1,17 +1,38 @@
-use std::fmt::Display;
+use core::{
+    cmp::Ordering,
+    fmt::Display,
+    iter::Sum,
+    ops::{Add, Sub},
+    str::FromStr,
+    time::Duration,
+};
 
-use crate::byte_handler::{ByteHandler, ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandler, ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::layout,
+    layout::LayoutError,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum InGameTimeError {
     #[error("Insufficiently Long Iterator")]
     InsufficientlyLongIterator,
+    #[error("Minutes are invalid")]
+    MinutesInvalid,
+    #[error("Seconds are invalid")]
+    SecondsInvalid,
+    #[error("Milliseconds are invalid")]
+    MillisecondsInvalid,
+    #[error("Time is not formatted as M:SS.mmm")]
+    InvalidFormat,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
 }
 
 // Struct size is 32 bits, copy is fine
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct InGameTime {
     minutes: u8,
     seconds: u8,
@@ -19,6 +40,15 @@ pub struct InGameTime {
 }
 
 impl InGameTime {
+    /// Largest amount of minutes that fits in the 7 bits of the packed form
+    pub const MAX_MINUTES: u8 = 0x7F;
+    /// Longest time that `from_millis` accepts, 127:59.999
+    pub const MAX: Self = Self {
+        minutes: Self::MAX_MINUTES,
+        seconds: 59,
+        milliseconds: 999,
+    };
+
     #[inline(always)]
     pub fn new(minutes: u8, seconds: u8, milliseconds: u16) -> Self {
         Self {
@@ -28,6 +58,34 @@ impl InGameTime {
         }
     }
 
+    /// Like `new`, but rejects values the game could never display
+    pub fn try_new(minutes: u8, seconds: u8, milliseconds: u16) -> Result<Self, InGameTimeError> {
+        if minutes > Self::MAX_MINUTES {
+            return Err(InGameTimeError::MinutesInvalid);
+        }
+        if seconds > 59 {
+            return Err(InGameTimeError::SecondsInvalid);
+        }
+        if milliseconds > 999 {
+            return Err(InGameTimeError::MillisecondsInvalid);
+        }
+
+        Ok(Self::new(minutes, seconds, milliseconds))
+    }
+
+    pub fn from_millis(millis: u32) -> Result<Self, InGameTimeError> {
+        let minutes = millis / 60000;
+        if minutes > Self::MAX_MINUTES as u32 {
+            return Err(InGameTimeError::MinutesInvalid);
+        }
+
+        Ok(Self::new(
+            minutes as u8,
+            ((millis / 1000) % 60) as u8,
+            (millis % 1000) as u16,
+        ))
+    }
+
     pub fn minutes(self) -> u8 {
         self.minutes
     }
@@ -40,17 +98,58 @@ impl InGameTime {
         self.milliseconds
     }
 
+    /// Returns false for times the game's timer can't show (5:59.999 is the latest finish)
     pub fn is_technically_valid(self) -> bool {
-        self.minutes > 5 || self.seconds > 59 || self.milliseconds > 999
+        self.minutes <= 5 && self.seconds <= 59 && self.milliseconds <= 999
     }
 
     pub fn igt_to_millis(self) -> i32 {
         (self.milliseconds as i32) + (self.seconds as i32) * 1000 + (self.minutes as i32) * 60000
     }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Self::from_millis(self.igt_to_millis() as u32 + rhs.igt_to_millis() as u32).ok()
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        let millis = self.igt_to_millis().checked_sub(rhs.igt_to_millis())?;
+        Self::from_millis(u32::try_from(millis).ok()?).ok()
+    }
+
+    /// Like `checked_add`, but stops at `InGameTime::MAX`
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).unwrap_or(Self::MAX)
+    }
+
+    /// Like `checked_sub`, but stops at zero (and at `InGameTime::MAX` for unnormalized times
+    /// such as 127:127.1023)
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        match self.igt_to_millis().checked_sub(rhs.igt_to_millis()) {
+            Some(millis) if millis > 0 => Self::saturating_from_millis(millis as u32),
+            _ => Self::default(),
+        }
+    }
+
+    /// Absolute difference between two times, e.g. to compare lap sums with the finish time.
+    /// Unnormalized times can be further apart than `InGameTime::MAX`, which is returned then.
+    pub fn abs_diff(self, other: Self) -> Self {
+        Self::saturating_from_millis(self.igt_to_millis().abs_diff(other.igt_to_millis()))
+    }
+
+    fn saturating_from_millis(millis: u32) -> Self {
+        Self::from_millis(millis).unwrap_or(Self::MAX)
+    }
+
+    /// Packs the time back into the 3 byte form used by RKG headers, see `FieldCodec::encode`
+    pub fn to_bytes(self) -> Result<[u8; 3], InGameTimeError> {
+        let mut bytes = [0; 3];
+        self.encode(&mut bytes)?;
+        Ok(bytes)
+    }
 }
 
 impl Display for InGameTime {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "{:02}:{:02}.{:03}",
@@ -59,6 +158,103 @@ impl Display for InGameTime {
     }
 }
 
+impl FromStr for InGameTime {
+    type Err = InGameTimeError;
+
+    /// Accepts the displayed form with 1 or 2 minute digits, i.e. `1:03.904` or `01:03.904`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (minutes, rest) = s.split_once(':').ok_or(InGameTimeError::InvalidFormat)?;
+        let (seconds, milliseconds) = rest.split_once('.').ok_or(InGameTimeError::InvalidFormat)?;
+
+        if !(1..=2).contains(&minutes.len()) || seconds.len() != 2 || milliseconds.len() != 3 {
+            return Err(InGameTimeError::InvalidFormat);
+        }
+        let parse = |digits: &str| {
+            if !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(InGameTimeError::InvalidFormat);
+            }
+            digits
+                .parse::<u16>()
+                .map_err(|_| InGameTimeError::InvalidFormat)
+        };
+
+        Self::try_new(
+            parse(minutes)? as u8,
+            parse(seconds)? as u8,
+            parse(milliseconds)?,
+        )
+    }
+}
+
+impl PartialOrd for InGameTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for InGameTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.igt_to_millis()
+            .cmp(&other.igt_to_millis())
+            .then_with(|| {
+                (self.minutes, self.seconds, self.milliseconds).cmp(&(
+                    other.minutes,
+                    other.seconds,
+                    other.milliseconds,
+                ))
+            })
+    }
+}
+
+impl Add for InGameTime {
+    type Output = Self;
+
+    /// Panics if the result is longer than `InGameTime::MAX`, see `checked_add` and `saturating_add`
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs).expect("attempt to add with overflow")
+    }
+}
+
+impl Sub for InGameTime {
+    type Output = Self;
+
+    /// Panics if `rhs` is longer than `self`, see `checked_sub`, `saturating_sub` and `abs_diff`
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl Sum for InGameTime {
+    /// Panics on overflow like `Add`
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a InGameTime> for InGameTime {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl From<InGameTime> for Duration {
+    fn from(value: InGameTime) -> Self {
+        Duration::from_millis(value.igt_to_millis() as u64)
+    }
+}
+
+impl TryFrom<Duration> for InGameTime {
+    type Error = InGameTimeError;
+
+    /// Truncates to whole milliseconds, like the game's timer
+    fn try_from(value: Duration) -> Result<Self, Self::Error> {
+        let millis =
+            u32::try_from(value.as_millis()).map_err(|_| InGameTimeError::MinutesInvalid)?;
+        Self::from_millis(millis)
+    }
+}
+
 impl FromByteHandler for InGameTime {
     type Err = InGameTimeError;
 
@@ -71,17 +267,34 @@ impl FromByteHandler for InGameTime {
         T: TryInto<ByteHandler>,
         Self::Err: From<T::Error>,
     {
-        let mut handler = handler.try_into()?;
-
-        handler.shift_right(1);
-        let minutes = handler.copy_byte(0);
-        let seconds = handler.copy_byte(1) >> 1;
-        handler.shift_left(9);
+        let handler = handler.try_into()?;
 
         Ok(Self {
-            minutes,
-            seconds,
-            milliseconds: handler.copy_word(0) & 0x3FF,
+            minutes: handler.read_field(layout::FINISH_MINUTES, 0x04)? as u8,
+            seconds: handler.read_field(layout::FINISH_SECONDS, 0x04)? as u8,
+            milliseconds: handler.read_field(layout::FINISH_MILLISECONDS, 0x04)? as u16,
         })
     }
 }
+
+impl FieldCodec for InGameTime {
+    const SIZE: usize = 3;
+    type Error = InGameTimeError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::FINISH_MINUTES
+            .relative_to(0x04)
+            .write(bytes, self.minutes as u32)?;
+        layout::FINISH_SECONDS
+            .relative_to(0x04)
+            .write(bytes, self.seconds as u32)?;
+        layout::FINISH_MILLISECONDS
+            .relative_to(0x04)
+            .write(bytes, self.milliseconds as u32)?;
+        Ok(())
+    }
+}
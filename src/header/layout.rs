@@ -0,0 +1,42 @@
+//! Where every value of the 0x88 byte header lives
+//! https://wiki.tockdom.com/wiki/RKG_(File_Format)#File_Header
+
+use crate::layout::bit_layout;
+
+/// Distance between two lap split times
+pub const LAP_SPLIT_STRIDE: usize = 0x03;
+/// Lap split times stored in every header, only `LAP_COUNT` of them are used
+pub const LAP_SPLIT_COUNT: usize = 10;
+pub const MII_OFFSET: usize = 0x3C;
+pub const MII_SIZE: usize = 0x4A;
+
+bit_layout! {
+    /// Every field of the header, in file order. Lap splits are only listed once, see `LAP_SPLIT_STRIDE`
+    HEADER_FIELDS {
+        /// "RKGD"
+        MAGIC: 0x00, 0, 32;
+        FINISH_MINUTES: 0x04, 0, 7;
+        FINISH_SECONDS: 0x04, 7, 7;
+        FINISH_MILLISECONDS: 0x05, 6, 10;
+        SLOT_ID: 0x07, 0, 6;
+        VEHICLE: 0x08, 0, 6;
+        CHARACTER: 0x08, 6, 6;
+        /// Years since 2000
+        YEAR: 0x09, 4, 7;
+        MONTH: 0x0A, 3, 4;
+        DAY: 0x0A, 7, 5;
+        CONTROLLER: 0x0B, 4, 4;
+        IS_COMPRESSED: 0x0C, 4, 1;
+        GHOST_TYPE: 0x0C, 7, 7;
+        IS_AUTOMATIC_DRIFT: 0x0D, 6, 1;
+        DECOMPRESSED_INPUT_DATA_LENGTH: 0x0E, 0, 16;
+        LAP_COUNT: 0x10, 0, 8;
+        LAP_MINUTES: 0x11, 0, 7;
+        LAP_SECONDS: 0x11, 7, 7;
+        LAP_MILLISECONDS: 0x12, 6, 10;
+        COUNTRY: 0x34, 0, 8;
+        SUBREGION: 0x35, 0, 8;
+        LOCATION_CODE: 0x36, 0, 16;
+        MII_CRC16: 0x86, 0, 16;
+    }
+}
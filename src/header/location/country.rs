@@ -1,5 +1,3 @@
-use crate::header::location::subregion::Subregion;
-
 #[derive(thiserror::Error, Debug)]
 pub enum CountryError {
     #[error("Nonexistent Country")]
@@ -273,8 +271,143 @@ pub enum Country {
     NotSet,
 }
 
-impl std::fmt::Display for Country {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl TryFrom<u8> for Country {
+    type Error = CountryError;
+    /// Only maps the countries of the unmodded Wii, IDs added by Extended Regions depend on its version
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::Japan),
+            8 => Ok(Self::Anguilla),
+            9 => Ok(Self::AntiguaAndBarbuda),
+            10 => Ok(Self::Argentina),
+            11 => Ok(Self::Aruba),
+            12 => Ok(Self::Bahamas),
+            13 => Ok(Self::Barbados),
+            14 => Ok(Self::Belize),
+            15 => Ok(Self::Bolivia),
+            16 => Ok(Self::Brazil),
+            17 => Ok(Self::BritishVirginIslands),
+            18 => Ok(Self::Canada),
+            19 => Ok(Self::CaymanIslands),
+            20 => Ok(Self::Chile),
+            21 => Ok(Self::Colombia),
+            22 => Ok(Self::CostaRica),
+            23 => Ok(Self::Dominica),
+            24 => Ok(Self::DominicanRepublic),
+            25 => Ok(Self::Ecuador),
+            26 => Ok(Self::ElSalvador),
+            27 => Ok(Self::FrenchGuiana),
+            28 => Ok(Self::Grenada),
+            29 => Ok(Self::Guadeloupe),
+            30 => Ok(Self::Guatemala),
+            31 => Ok(Self::Guyana),
+            32 => Ok(Self::Haiti),
+            33 => Ok(Self::Honduras),
+            34 => Ok(Self::Jamaica),
+            35 => Ok(Self::Martinique),
+            36 => Ok(Self::Mexico),
+            37 => Ok(Self::Montserrat),
+            38 => Ok(Self::NetherlandsAntilles),
+            39 => Ok(Self::Nicaragua),
+            40 => Ok(Self::Panama),
+            41 => Ok(Self::Paraguay),
+            42 => Ok(Self::Peru),
+            43 => Ok(Self::StKittsAndNevis),
+            44 => Ok(Self::StLucia),
+            45 => Ok(Self::StVincentAndTheGrenadines),
+            46 => Ok(Self::Suriname),
+            47 => Ok(Self::TrinidadAndTobago),
+            48 => Ok(Self::TurksAndCaicosIslands),
+            49 => Ok(Self::UnitedStates),
+            50 => Ok(Self::Uruguay),
+            51 => Ok(Self::USVirginIslands),
+            52 => Ok(Self::Venezuela),
+            64 => Ok(Self::Albania),
+            65 => Ok(Self::Australia),
+            66 => Ok(Self::Austria),
+            67 => Ok(Self::Belgium),
+            68 => Ok(Self::BosniaHerzegovina),
+            69 => Ok(Self::Botswana),
+            70 => Ok(Self::Bulgaria),
+            71 => Ok(Self::Croatia),
+            72 => Ok(Self::Cyprus),
+            73 => Ok(Self::Czechia),
+            74 => Ok(Self::Denmark),
+            75 => Ok(Self::Estonia),
+            76 => Ok(Self::Finland),
+            77 => Ok(Self::France),
+            78 => Ok(Self::Germany),
+            79 => Ok(Self::Greece),
+            80 => Ok(Self::Hungary),
+            81 => Ok(Self::Iceland),
+            82 => Ok(Self::Ireland),
+            83 => Ok(Self::Italy),
+            84 => Ok(Self::Latvia),
+            85 => Ok(Self::Lesotho),
+            86 => Ok(Self::Liechtenstein),
+            87 => Ok(Self::Lithuania),
+            88 => Ok(Self::Luxembourg),
+            89 => Ok(Self::NorthMacedonia),
+            90 => Ok(Self::Malta),
+            91 => Ok(Self::Montenegro),
+            92 => Ok(Self::Mozambique),
+            93 => Ok(Self::Namibia),
+            94 => Ok(Self::Netherlands),
+            95 => Ok(Self::NewZealand),
+            96 => Ok(Self::Norway),
+            97 => Ok(Self::Poland),
+            98 => Ok(Self::Portugal),
+            99 => Ok(Self::Romania),
+            100 => Ok(Self::Russia),
+            101 => Ok(Self::Serbia),
+            102 => Ok(Self::Slovakia),
+            103 => Ok(Self::Slovenia),
+            104 => Ok(Self::SouthAfrica),
+            105 => Ok(Self::Spain),
+            106 => Ok(Self::Eswatini),
+            107 => Ok(Self::Sweden),
+            108 => Ok(Self::Switzerland),
+            109 => Ok(Self::Turkey),
+            110 => Ok(Self::UnitedKingdom),
+            111 => Ok(Self::Zambia),
+            112 => Ok(Self::Zimbabwe),
+            113 => Ok(Self::Azerbaijan),
+            114 => Ok(Self::Mauritania),
+            115 => Ok(Self::Mali),
+            116 => Ok(Self::Niger),
+            117 => Ok(Self::Chad),
+            118 => Ok(Self::Sudan),
+            119 => Ok(Self::Eritrea),
+            120 => Ok(Self::Djibouti),
+            121 => Ok(Self::Somalia),
+            128 => Ok(Self::Taiwan),
+            136 => Ok(Self::SouthKorea),
+            144 => Ok(Self::HongKong),
+            145 => Ok(Self::Macao),
+            152 => Ok(Self::Indonesia),
+            153 => Ok(Self::Singapore),
+            154 => Ok(Self::Thailand),
+            155 => Ok(Self::Philippines),
+            156 => Ok(Self::Malaysia),
+            160 => Ok(Self::China),
+            168 => Ok(Self::UnitedArabEmirates),
+            169 => Ok(Self::India),
+            170 => Ok(Self::Egypt),
+            171 => Ok(Self::Oman),
+            172 => Ok(Self::Qatar),
+            173 => Ok(Self::Kuwait),
+            174 => Ok(Self::SaudiArabia),
+            175 => Ok(Self::Syria),
+            176 => Ok(Self::Bahrain),
+            177 => Ok(Self::Jordan),
+            255 => Ok(Self::NotSet),
+            _ => Err(CountryError::NonexistentCountry),
+        }
+    }
+}
+
+impl core::fmt::Display for Country {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Country::Japan => write!(f, "Japan"),
             Country::Antarctica => write!(f, "Antarctica"),
@@ -4,6 +4,7 @@ pub mod country;
 pub mod subregion;
 
 /// Represents the country and subregion of the player. https://docs.google.com/spreadsheets/d/1mSAomO_msfNllNsPeXbgU6UbJaGV5t6NvbZi6ebPFx4/edit?usp=sharing
+#[allow(dead_code)] // TODO: Read once Location::new is implemented
 pub struct Location {
     country: Country,
     subregion: Subregion,
@@ -11,8 +12,12 @@ pub struct Location {
 }
 
 impl Location {
-    pub fn new(country_id: u8, subregion_id: u8, known_version: Option<LocationVersioning>) {
-        let mut known_version = match known_version {
+    pub fn new(
+        country_id: u8,
+        _subregion_id: u8,
+        known_version: Option<LocationVersioning>,
+    ) -> Self {
+        let _known_version = match known_version {
             None => LocationVersioning::get_min_from_country_id_number(country_id),
             Some(v) => v,
         };
@@ -0,0 +1,185 @@
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+use crate::header::mii::{Mii, glyphs};
+
+/// Which of the two UTF-16 name fields of a Mii an issue was found in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MiiNameField {
+    Name,
+    CreatorName,
+}
+
+impl Display for MiiNameField {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Name => write!(f, "name"),
+            Self::CreatorName => write!(f, "creator name"),
+        }
+    }
+}
+
+/// A value in the Mii data that the Mii Channel could never have produced
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MiiAuditIssue {
+    EmptyName,
+    /// UTF-16 code unit outside of what the Wii keyboards can type
+    InvalidNameCharacter {
+        field: MiiNameField,
+        code_unit: u16,
+    },
+    /// A part position/scale/rotation outside of the range the Mii Channel editor allows
+    OutOfRange {
+        field: &'static str,
+        value: u8,
+        min: u8,
+        max: u8,
+    },
+    /// Birthday month is unset but a day is stored anyway
+    BirthdayDayWithoutMonth {
+        day: u8,
+    },
+    /// Special Miis can never be sent out through Mingle
+    SpecialMiiMingles,
+    /// Miis from other consoles can't have Mingle turned back on
+    ForeignMiiMingles,
+    /// Downloaded Miis always come from another console
+    DownloadedButNotForeign,
+    /// Miis are only made favorites in the Plaza, and copying a downloaded Mii there clears
+    /// its downloaded flag
+    DownloadedFavorite,
+}
+
+impl Display for MiiAuditIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::EmptyName => write!(f, "Name is empty"),
+            Self::InvalidNameCharacter { field, code_unit } => {
+                write!(f, "Invalid character U+{code_unit:04X} in {field}")
+            }
+            Self::OutOfRange {
+                field,
+                value,
+                min,
+                max,
+            } => write!(f, "{field} is {value}, expected {min}..={max}"),
+            Self::BirthdayDayWithoutMonth { day } => {
+                write!(f, "Birthday day {day} is set without a month")
+            }
+            Self::SpecialMiiMingles => write!(f, "Special Mii has Mingle enabled"),
+            Self::ForeignMiiMingles => write!(f, "Foreign Mii has Mingle enabled"),
+            Self::DownloadedButNotForeign => write!(f, "Downloaded Mii is not foreign"),
+            Self::DownloadedFavorite => write!(f, "Downloaded Mii is marked as favorite"),
+        }
+    }
+}
+
+/// Field name, getter, minimum and maximum
+type PartRange = (&'static str, fn(&Mii) -> u8, u8, u8);
+
+/// Ranges of the sliders in the Mii Channel editor
+/// http://wiibrew.org/wiki/Mii_Data#Mii_format
+const PART_RANGES: [PartRange; 19] = [
+    ("eyebrows.rotation", |m| m.eyebrows().rotation(), 0, 11),
+    ("eyebrows.size", |m| m.eyebrows().size(), 0, 8),
+    ("eyebrows.y", |m| m.eyebrows().y(), 3, 18),
+    ("eyebrows.x", |m| m.eyebrows().x(), 0, 12),
+    ("eyes.rotation", |m| m.eyes().rotation(), 0, 7),
+    ("eyes.size", |m| m.eyes().size(), 0, 7),
+    ("eyes.y", |m| m.eyes().y(), 0, 18),
+    ("eyes.x", |m| m.eyes().x(), 0, 12),
+    ("nose.size", |m| m.nose().size(), 0, 8),
+    ("nose.y", |m| m.nose().y(), 0, 18),
+    ("lips.size", |m| m.lips().size(), 0, 8),
+    ("lips.y", |m| m.lips().y(), 0, 18),
+    ("glasses.size", |m| m.glasses().size(), 0, 7),
+    ("glasses.y", |m| m.glasses().y(), 0, 20),
+    (
+        "facial_hair.mustache_size",
+        |m| m.facial_hair().mustache_size(),
+        0,
+        8,
+    ),
+    (
+        "facial_hair.mustache_y",
+        |m| m.facial_hair().mustache_y(),
+        0,
+        16,
+    ),
+    ("mole.size", |m| m.mole().size(), 0, 8),
+    ("mole.y", |m| m.mole().y(), 0, 30),
+    ("mole.x", |m| m.mole().x(), 0, 16),
+];
+
+pub(crate) fn audit(mii: &Mii) -> Vec<MiiAuditIssue> {
+    let mut issues = Vec::new();
+    let bytes = &mii.bytes;
+
+    let month = (bytes[0] >> 2) & 0x0F;
+    let day = ((bytes[0] & 0x03) << 3) | (bytes[1] >> 5);
+    if month == 0 && day != 0 {
+        issues.push(MiiAuditIssue::BirthdayDayWithoutMonth { day });
+    }
+
+    if bytes[0x02..=0x03] == [0, 0] {
+        issues.push(MiiAuditIssue::EmptyName);
+    }
+    audit_name(&bytes[0x02..=0x15], MiiNameField::Name, &mut issues);
+    audit_name(&bytes[0x36..=0x49], MiiNameField::CreatorName, &mut issues);
+
+    for (field, read, min, max) in PART_RANGES {
+        let value = read(mii);
+        if value < min || value > max {
+            issues.push(MiiAuditIssue::OutOfRange {
+                field,
+                value,
+                min,
+                max,
+            });
+        }
+    }
+
+    if mii.mii_id().is_special() && !mii.mingle_off() {
+        issues.push(MiiAuditIssue::SpecialMiiMingles);
+    }
+    if mii.mii_id().is_foreign() && !mii.mingle_off() {
+        issues.push(MiiAuditIssue::ForeignMiiMingles);
+    }
+    if mii.downloaded() && !mii.mii_id().is_foreign() {
+        issues.push(MiiAuditIssue::DownloadedButNotForeign);
+    }
+    if mii.downloaded() && mii.is_favorite() {
+        issues.push(MiiAuditIssue::DownloadedFavorite);
+    }
+
+    issues
+}
+
+fn audit_name(bytes: &[u8], field: MiiNameField, issues: &mut Vec<MiiAuditIssue>) {
+    // Bytes after the null terminator are left over from previous names, so they aren't checked
+    let code_units = bytes
+        .chunks_exact(2)
+        .map(|c| u16::from_be_bytes([c[0], c[1]]))
+        .take_while(|&u| u != 0);
+
+    for code_unit in code_units {
+        if !is_typeable(code_unit) {
+            issues.push(MiiAuditIssue::InvalidNameCharacter { field, code_unit });
+        }
+    }
+}
+
+/// Character ranges reachable from the Wii keyboards of every region, plus the symbols and
+/// private use glyphs listed in `glyphs`
+fn is_typeable(code_unit: u16) -> bool {
+    matches!(
+        code_unit,
+        0x0020..=0x007E
+            | 0x00A0..=0x017F
+            | 0x0370..=0x04FF
+            | 0x3000..=0x30FF
+            | 0x4E00..=0x9FFF
+            | 0xAC00..=0xD7A3
+            | 0xFF00..=0xFFEF
+    ) || char::from_u32(code_unit as u32).is_some_and(glyphs::is_keyboard_symbol)
+}
@@ -1,6 +1,10 @@
-use std::convert::Infallible;
+use core::convert::Infallible;
 
-use crate::byte_handler::{ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::mii::layout,
+    layout::LayoutError,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum BirthdayError {
@@ -10,6 +14,8 @@ pub enum BirthdayError {
     DayInvalid,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
     #[error("")]
     Infallible(#[from] Infallible),
 }
@@ -62,8 +68,29 @@ impl FromByteHandler for Birthday {
         T: TryInto<crate::byte_handler::ByteHandler>,
         Self::Err: From<T::Error>,
     {
-        let mut handler = handler.try_into()?;
-        handler.shift_right(2);
-        Self::new(handler.copy_byte(0) & 0x0F, handler.copy_byte(1) >> 3)
+        let handler = handler.try_into()?;
+        Self::new(
+            handler.read_field(layout::BIRTHDAY_MONTH, 0x00)? as u8,
+            handler.read_field(layout::BIRTHDAY_DAY, 0x00)? as u8,
+        )
+    }
+}
+
+impl FieldCodec for Birthday {
+    const SIZE: usize = 2;
+    type Error = BirthdayError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::BIRTHDAY_MONTH
+            .relative_to(0x00)
+            .write(bytes, self.month.unwrap_or(0) as u32)?;
+        layout::BIRTHDAY_DAY
+            .relative_to(0x00)
+            .write(bytes, self.day.unwrap_or(0) as u32)?;
+        Ok(())
     }
 }
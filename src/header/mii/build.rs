@@ -1,6 +1,10 @@
-use std::convert::Infallible;
+use core::convert::Infallible;
 
-use crate::byte_handler::{ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::mii::layout,
+    layout::LayoutError,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum BuildError {
@@ -10,6 +14,8 @@ pub enum BuildError {
     HeightInvalid,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
     #[error("")]
     Infallible(#[from] Infallible),
 }
@@ -50,6 +56,28 @@ impl FromByteHandler for Build {
     {
         let handler = handler.try_into()?;
 
-        Self::new(handler.copy_byte(0), handler.copy_byte(1))
+        Self::new(
+            handler.read_field(layout::HEIGHT, 0x16)? as u8,
+            handler.read_field(layout::WEIGHT, 0x16)? as u8,
+        )
+    }
+}
+
+impl FieldCodec for Build {
+    const SIZE: usize = 2;
+    type Error = BuildError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::HEIGHT
+            .relative_to(0x16)
+            .write(bytes, self.height as u32)?;
+        layout::WEIGHT
+            .relative_to(0x16)
+            .write(bytes, self.weight as u32)?;
+        Ok(())
     }
 }
@@ -1,10 +1,12 @@
-use std::convert::Infallible;
+use core::convert::Infallible;
 
 use crate::{
-    byte_handler::{ByteHandlerError, FromByteHandler},
-    header::mii::hair::HairColor,
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::mii::{hair::HairColor, layout},
+    layout::LayoutError,
 };
 
+#[derive(Clone)]
 pub struct Eyebrows {
     rotation: u8,
     size: u8,
@@ -43,6 +45,8 @@ pub enum EyebrowsError {
     ColorInvalid,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
     #[error("")]
     Infallible(#[from] Infallible),
 }
@@ -54,18 +58,17 @@ impl FromByteHandler for Eyebrows {
         T: TryInto<crate::byte_handler::ByteHandler>,
         Self::Err: From<T::Error>,
     {
-        let mut handler = handler.try_into()?;
+        let handler = handler.try_into()?;
+        let field = |field| handler.read_field(field, 0x24).map(|value| value as u8);
 
-        let x = handler.copy_byte(3) & 0x0F;
-        let eyebrow_type = EyebrowType::try_from(handler.copy_byte(0) >> 3)
+        let eyebrow_type = EyebrowType::try_from(field(layout::EYEBROW_TYPE)?)
             .map_err(|_| EyebrowsError::TypeInvalid)?;
-        let eyebrow_color = HairColor::try_from(handler.copy_byte(2) >> 5)
+        let eyebrow_color = HairColor::try_from(field(layout::EYEBROW_COLOR)?)
             .map_err(|_| EyebrowsError::ColorInvalid)?;
-        handler.shift_right(1);
-        let y = handler.copy_byte(3) >> 3;
-        let size = handler.copy_byte(2) & 0x0F;
-        handler.shift_right(2);
-        let rotation = handler.copy_byte(1) >> 3;
+        let rotation = field(layout::EYEBROW_ROTATION)?;
+        let size = field(layout::EYEBROW_SIZE)?;
+        let y = field(layout::EYEBROW_Y)?;
+        let x = field(layout::EYEBROW_X)?;
         Ok(Self {
             size,
             rotation,
@@ -137,3 +140,65 @@ impl TryFrom<u8> for EyebrowType {
         }
     }
 }
+
+impl From<EyebrowType> for u8 {
+    fn from(value: EyebrowType) -> Self {
+        match value {
+            EyebrowType::FlatAngledLarge => 0x06,
+            EyebrowType::LowArchRoundedThin => 0x00,
+            EyebrowType::SoftAngledLarge => 0x0c,
+            EyebrowType::MediumArchRoundedThin => 0x01,
+            EyebrowType::RoundedMedium => 0x09,
+            EyebrowType::LowArchMedium => 0x13,
+            EyebrowType::RoundedThin => 0x07,
+            EyebrowType::UpThin => 0x15,
+            EyebrowType::MediumArchRoundedMedium => 0x08,
+            EyebrowType::RoundedLarge => 0x11,
+            EyebrowType::UpLarge => 0x05,
+            EyebrowType::FlatAngledLargeInverted => 0x04,
+            EyebrowType::MediumArchFlat => 0x0B,
+            EyebrowType::AngledThin => 0x0A,
+            EyebrowType::HorizontalLarge => 0x02,
+            EyebrowType::HighArchFlat => 0x03,
+            EyebrowType::Flat => 0x0E,
+            EyebrowType::MediumArchLarge => 0x14,
+            EyebrowType::LowArchThin => 0x0F,
+            EyebrowType::RoundedThinInverted => 0x0D,
+            EyebrowType::HighArchLarge => 0x16,
+            EyebrowType::Hairy => 0x12,
+            EyebrowType::Dotted => 0x10,
+            EyebrowType::None => 0x17,
+        }
+    }
+}
+
+impl FieldCodec for Eyebrows {
+    const SIZE: usize = 4;
+    type Error = EyebrowsError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::EYEBROW_TYPE
+            .relative_to(0x24)
+            .write(bytes, u8::from(self.eyebrow_type) as u32)?;
+        layout::EYEBROW_ROTATION
+            .relative_to(0x24)
+            .write(bytes, self.rotation as u32)?;
+        layout::EYEBROW_COLOR
+            .relative_to(0x24)
+            .write(bytes, u8::from(self.eyebrow_color) as u32)?;
+        layout::EYEBROW_SIZE
+            .relative_to(0x24)
+            .write(bytes, self.size as u32)?;
+        layout::EYEBROW_Y
+            .relative_to(0x24)
+            .write(bytes, self.y as u32)?;
+        layout::EYEBROW_X
+            .relative_to(0x24)
+            .write(bytes, self.x as u32)?;
+        Ok(())
+    }
+}
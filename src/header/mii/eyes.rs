@@ -1,7 +1,12 @@
-use std::convert::Infallible;
+use core::convert::Infallible;
 
-use crate::byte_handler::{ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::mii::layout,
+    layout::LayoutError,
+};
 
+#[derive(Clone)]
 pub struct Eyes {
     rotation: u8,
     size: u8,
@@ -40,6 +45,8 @@ pub enum EyesError {
     ColorInvalid,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
     #[error("")]
     Infallible(#[from] Infallible),
 }
@@ -51,17 +58,17 @@ impl FromByteHandler for Eyes {
         T: TryInto<crate::byte_handler::ByteHandler>,
         Self::Err: From<T::Error>,
     {
-        let mut handler = handler.try_into()?;
+        let handler = handler.try_into()?;
+        let field = |field| handler.read_field(field, 0x28).map(|value| value as u8);
 
         let eye_type =
-            EyeType::try_from(handler.copy_byte(0) >> 2).map_err(|_| EyesError::TypeInvalid)?;
+            EyeType::try_from(field(layout::EYE_TYPE)?).map_err(|_| EyesError::TypeInvalid)?;
         let eye_color =
-            EyeColor::try_from(handler.copy_byte(2) >> 5).map_err(|_| EyesError::ColorInvalid)?;
-        let y = handler.copy_byte(1) & 0x1F;
-        handler.shift_right(5);
-        let rotation = handler.copy_byte(1) & 0x1F;
-        let x = handler.copy_byte(3) & 0x0F;
-        let size = handler.copy_byte(3) >> 4;
+            EyeColor::try_from(field(layout::EYE_COLOR)?).map_err(|_| EyesError::ColorInvalid)?;
+        let y = field(layout::EYE_Y)?;
+        let rotation = field(layout::EYE_ROTATION)?;
+        let x = field(layout::EYE_X)?;
+        let size = field(layout::EYE_SIZE)?;
 
         Ok(Self {
             size,
@@ -99,6 +106,19 @@ impl TryFrom<u8> for EyeColor {
     }
 }
 
+impl From<EyeColor> for u8 {
+    fn from(value: EyeColor) -> Self {
+        match value {
+            EyeColor::Black => 0x00,
+            EyeColor::Gray => 0x01,
+            EyeColor::Brown => 0x02,
+            EyeColor::Hazel => 0x03,
+            EyeColor::Blue => 0x04,
+            EyeColor::Green => 0x05,
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum EyeType {
     Normal,
@@ -207,3 +227,89 @@ impl TryFrom<u8> for EyeType {
         }
     }
 }
+
+impl From<EyeType> for u8 {
+    fn from(value: EyeType) -> Self {
+        match value {
+            EyeType::Normal => 0x02,
+            EyeType::NormalLash => 0x04,
+            EyeType::WhiteLash => 0x00,
+            EyeType::WhiteNoBottom => 0x08,
+            EyeType::OvalAngledWhite => 0x27,
+            EyeType::AngryWhite => 0x11,
+            EyeType::DotLashType1 => 0x01,
+            EyeType::Line => 0x1A,
+            EyeType::DotLine => 0x10,
+            EyeType::OvalWhite => 0x0F,
+            EyeType::RoundedWhite => 0x1B,
+            EyeType::NormalShadow => 0x14,
+            EyeType::CircleWhite => 0x21,
+            EyeType::Circle => 0x0B,
+            EyeType::CircleWhiteStroke => 0x13,
+            EyeType::NormalOvalNoBottom => 0x20,
+            EyeType::NormalOvalLarge => 0x09,
+            EyeType::NormalRoundedNoBottom => 0x0C,
+            EyeType::SmallLash => 0x17,
+            EyeType::Small => 0x22,
+            EyeType::TwoSmall => 0x15,
+            EyeType::NormalLongLash => 0x19,
+            EyeType::WhiteTwoLashes => 0x28,
+            EyeType::WhiteThreeLashes => 0x23,
+            EyeType::DotAngry => 0x05,
+            EyeType::DotAngled => 0x29,
+            EyeType::Oval => 0x0D,
+            EyeType::SmallWhite => 0x24,
+            EyeType::WhiteAngledNoBottom => 0x25,
+            EyeType::WhiteAngledNoLeft => 0x06,
+            EyeType::SmallWhiteTwoLashes => 0x18,
+            EyeType::LeafWhiteLash => 0x1E,
+            EyeType::WhiteLargeNoBottom => 0x1F,
+            EyeType::Dot => 0x12,
+            EyeType::DotLashType2 => 0x1C,
+            EyeType::DotThreeLashes => 0x2E,
+            EyeType::WhiteOvalTop => 0x07,
+            EyeType::WhiteOvalBottom => 0x2C,
+            EyeType::WhiteOvalBottomFlat => 0x26,
+            EyeType::WhiteOvalTwoLashes => 0x2A,
+            EyeType::WhiteOvalThreeLashes => 0x2D,
+            EyeType::WhiteOvalNoBottomTwoLashes => 0x1D,
+            EyeType::DotWhite => 0x03,
+            EyeType::WhiteOvalTopFlat => 0x2B,
+            EyeType::WhiteThinLeaf => 0x16,
+            EyeType::StarThreeLashes => 0x0A,
+            EyeType::LineTwoLashes => 0x0E,
+            EyeType::CrowsFeet => 0x2F,
+        }
+    }
+}
+
+impl FieldCodec for Eyes {
+    const SIZE: usize = 4;
+    type Error = EyesError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::EYE_TYPE
+            .relative_to(0x28)
+            .write(bytes, u8::from(self.eye_type) as u32)?;
+        layout::EYE_ROTATION
+            .relative_to(0x28)
+            .write(bytes, self.rotation as u32)?;
+        layout::EYE_Y
+            .relative_to(0x28)
+            .write(bytes, self.y as u32)?;
+        layout::EYE_COLOR
+            .relative_to(0x28)
+            .write(bytes, u8::from(self.eye_color) as u32)?;
+        layout::EYE_SIZE
+            .relative_to(0x28)
+            .write(bytes, self.size as u32)?;
+        layout::EYE_X
+            .relative_to(0x28)
+            .write(bytes, self.x as u32)?;
+        Ok(())
+    }
+}
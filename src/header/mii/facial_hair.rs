@@ -1,10 +1,12 @@
-use std::convert::Infallible;
+use core::convert::Infallible;
 
 use crate::{
-    byte_handler::{ByteHandlerError, FromByteHandler},
-    header::mii::hair::HairColor,
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::mii::{hair::HairColor, layout},
+    layout::LayoutError,
 };
 
+#[derive(Clone)]
 pub struct FacialHair {
     beard_type: BeardType,
     mustache_type: MustacheType,
@@ -37,17 +39,17 @@ impl FromByteHandler for FacialHair {
         T: TryInto<crate::byte_handler::ByteHandler>,
         Self::Err: From<T::Error>,
     {
-        let mut handler = handler.try_into()?;
-        let mustache_y = handler.copy_byte(1) & 0x1F;
-        handler.shift_right(1);
+        let handler = handler.try_into()?;
+        let field = |field| handler.read_field(field, 0x32).map(|value| value as u8);
+
         Ok(Self {
-            mustache_y,
-            mustache_size: handler.copy_byte(1) >> 4,
-            color: HairColor::try_from(handler.copy_byte(0) & 0x07)
+            mustache_y: field(layout::MUSTACHE_Y)?,
+            mustache_size: field(layout::MUSTACHE_SIZE)?,
+            color: HairColor::try_from(field(layout::FACIAL_HAIR_COLOR)?)
                 .map_err(|_| FacialHairError::ColorInvalid)?,
-            mustache_type: MustacheType::try_from(handler.copy_byte(0) >> 5)
+            mustache_type: MustacheType::try_from(field(layout::MUSTACHE_TYPE)?)
                 .map_err(|_| FacialHairError::MustacheTypeInvalid)?,
-            beard_type: BeardType::try_from((handler.copy_byte(0) >> 3) & 0x03)
+            beard_type: BeardType::try_from(field(layout::BEARD_TYPE)?)
                 .map_err(|_| FacialHairError::BeardTypeInvalid)?,
         })
     }
@@ -63,6 +65,8 @@ pub enum FacialHairError {
     ColorInvalid,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
     #[error("")]
     Infallible(#[from] Infallible),
 }
@@ -87,6 +91,17 @@ impl TryFrom<u8> for BeardType {
     }
 }
 
+impl From<BeardType> for u8 {
+    fn from(value: BeardType) -> Self {
+        match value {
+            BeardType::None => 0,
+            BeardType::Goatee => 1,
+            BeardType::GoateeLong => 2,
+            BeardType::LionsManeLong => 3,
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum MustacheType {
     None,
@@ -106,3 +121,42 @@ impl TryFrom<u8> for MustacheType {
         }
     }
 }
+
+impl From<MustacheType> for u8 {
+    fn from(value: MustacheType) -> Self {
+        match value {
+            MustacheType::None => 0,
+            MustacheType::Walrus => 1,
+            MustacheType::Pencil => 2,
+            MustacheType::Horseshoe => 3,
+        }
+    }
+}
+
+impl FieldCodec for FacialHair {
+    const SIZE: usize = 2;
+    type Error = FacialHairError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::MUSTACHE_TYPE
+            .relative_to(0x32)
+            .write(bytes, u8::from(self.mustache_type) as u32)?;
+        layout::BEARD_TYPE
+            .relative_to(0x32)
+            .write(bytes, u8::from(self.beard_type) as u32)?;
+        layout::FACIAL_HAIR_COLOR
+            .relative_to(0x32)
+            .write(bytes, u8::from(self.color) as u32)?;
+        layout::MUSTACHE_SIZE
+            .relative_to(0x32)
+            .write(bytes, self.mustache_size as u32)?;
+        layout::MUSTACHE_Y
+            .relative_to(0x32)
+            .write(bytes, self.mustache_y as u32)?;
+        Ok(())
+    }
+}
@@ -1,6 +1,10 @@
-use std::convert::Infallible;
+use core::convert::Infallible;
 
-use crate::byte_handler::{ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::mii::layout,
+    layout::LayoutError,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum FavColorError {
@@ -8,6 +12,8 @@ pub enum FavColorError {
     ColorInvalid,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
     #[error("")]
     Infallible(#[from] Infallible),
 }
@@ -78,6 +84,22 @@ impl FromByteHandler for FavColor {
         Self::Err: From<T::Error>,
     {
         let handler = handler.try_into()?;
-        Self::try_from((handler.copy_byte(0) >> 1) & 0x0F)
+        Self::try_from(handler.read_field(layout::FAVORITE_COLOR, 0x01)? as u8)
+    }
+}
+
+impl FieldCodec for FavColor {
+    const SIZE: usize = 1;
+    type Error = FavColorError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::FAVORITE_COLOR
+            .relative_to(0x01)
+            .write(bytes, u8::from(*self) as u32)?;
+        Ok(())
     }
 }
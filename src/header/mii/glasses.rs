@@ -1,6 +1,10 @@
-use std::convert::Infallible;
+use core::convert::Infallible;
 
-use crate::byte_handler::{ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::mii::layout,
+    layout::LayoutError,
+};
 
 #[derive(Clone, Copy)]
 pub struct Glasses {
@@ -32,15 +36,15 @@ impl FromByteHandler for Glasses {
         T: TryInto<crate::byte_handler::ByteHandler>,
         Self::Err: From<T::Error>,
     {
-        let mut handler = handler.try_into()?;
+        let handler = handler.try_into()?;
+        let field = |field| handler.read_field(field, 0x30).map(|value| value as u8);
 
-        let glasses_type = GlassesType::try_from(handler.copy_byte(0) >> 4)
+        let glasses_type = GlassesType::try_from(field(layout::GLASSES_TYPE)?)
             .map_err(|_| GlassesError::TypeInvalid)?;
-        let y = handler.copy_byte(1) & 0x1F;
-        handler.shift_right(1);
-        let glasses_color = GlassesColor::try_from(handler.copy_byte(0) & 0x03)
+        let y = field(layout::GLASSES_Y)?;
+        let glasses_color = GlassesColor::try_from(field(layout::GLASSES_COLOR)?)
             .map_err(|_| GlassesError::ColorInvalid)?;
-        let size = handler.copy_byte(1) >> 4;
+        let size = field(layout::GLASSES_SIZE)?;
 
         Ok(Self {
             glasses_type,
@@ -59,6 +63,8 @@ pub enum GlassesError {
     ColorInvalid,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
     #[error("")]
     Infallible(#[from] Infallible),
 }
@@ -87,6 +93,19 @@ impl TryFrom<u8> for GlassesColor {
     }
 }
 
+impl From<GlassesColor> for u8 {
+    fn from(value: GlassesColor) -> Self {
+        match value {
+            GlassesColor::Black => 0,
+            GlassesColor::Brown => 1,
+            GlassesColor::Red => 2,
+            GlassesColor::Blue => 3,
+            GlassesColor::Yellow => 4,
+            GlassesColor::Gray => 5,
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum GlassesType {
     None,
@@ -117,3 +136,44 @@ impl TryFrom<u8> for GlassesType {
         }
     }
 }
+
+impl From<GlassesType> for u8 {
+    fn from(value: GlassesType) -> Self {
+        match value {
+            GlassesType::None => 0,
+            GlassesType::Square => 1,
+            GlassesType::Rectangle => 2,
+            GlassesType::Rounded => 3,
+            GlassesType::Oval => 4,
+            GlassesType::CatEye => 5,
+            GlassesType::SemiOpaqueAviator => 6,
+            GlassesType::SemiOpaqueRectangle => 7,
+            GlassesType::SemiOpaqueCatEye => 8,
+        }
+    }
+}
+
+impl FieldCodec for Glasses {
+    const SIZE: usize = 2;
+    type Error = GlassesError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::GLASSES_TYPE
+            .relative_to(0x30)
+            .write(bytes, u8::from(self.glasses_type) as u32)?;
+        layout::GLASSES_COLOR
+            .relative_to(0x30)
+            .write(bytes, u8::from(self.glasses_color) as u32)?;
+        layout::GLASSES_SIZE
+            .relative_to(0x30)
+            .write(bytes, self.size as u32)?;
+        layout::GLASSES_Y
+            .relative_to(0x30)
+            .write(bytes, self.y as u32)?;
+        Ok(())
+    }
+}
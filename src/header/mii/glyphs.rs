@@ -0,0 +1,122 @@
+// Nintendo draws its own symbols (buttons, faces, weather, card suits...) in the private use area
+// of the Wii system font. They are valid UTF-16 but render as tofu everywhere else.
+
+use alloc::string::String;
+
+/// Private use area code points of the Wii system font and their closest Unicode equivalent
+const GLYPH_TABLE: [(char, char); 29] = [
+    ('\u{E000}', 'Ⓐ'),         // A Button
+    ('\u{E001}', 'Ⓑ'),         // B Button
+    ('\u{E002}', '⌂'),         // HOME Button
+    ('\u{E003}', '⊕'),         // + Button
+    ('\u{E004}', '⊖'),         // - Button
+    ('\u{E005}', '①'),         // 1 Button
+    ('\u{E006}', '②'),         // 2 Button
+    ('\u{E007}', '✜'),         // +Control Pad
+    ('\u{E008}', '☺'),         // Happy face
+    ('\u{E009}', '\u{1F620}'), // Angry face
+    ('\u{E00A}', '☹'),         // Sad face
+    ('\u{E00B}', '\u{1F610}'), // Expressionless face
+    ('\u{E00C}', '☀'),         // Sun
+    ('\u{E00D}', '☁'),         // Cloud
+    ('\u{E00E}', '☂'),         // Umbrella
+    ('\u{E00F}', '☃'),         // Snowman
+    ('\u{E010}', '❗'),        // Exclamation mark
+    ('\u{E011}', '❓'),        // Question mark
+    ('\u{E012}', '✉'),         // Letter
+    ('\u{E013}', '\u{1F4F1}'), // Mobile phone
+    ('\u{E014}', '⏰'),        // Alarm clock
+    ('\u{E015}', '♠'),         // Spade
+    ('\u{E016}', '♦'),         // Diamond
+    ('\u{E017}', '♥'),         // Heart
+    ('\u{E018}', '♣'),         // Club
+    ('\u{E019}', '➡'),         // Right arrow
+    ('\u{E01A}', '⬅'),         // Left arrow
+    ('\u{E01B}', '⬆'),         // Up arrow
+    ('\u{E01C}', '⬇'),         // Down arrow
+];
+
+/// Symbols between General Punctuation and Miscellaneous Symbols (U+2000..=U+2BFF) on the symbol
+/// pages of the Wii keyboards, the rest of that block can't be typed
+const KEYBOARD_SYMBOLS: [char; 82] = [
+    '‐', '–', '—', '‖', '‘', '’', '“', '”', '†', '‡', '•', '‥', '…', '‰', '′', '″', '※', '‼', '€',
+    '℃', '№', '™', '\u{212B}', '←', '↑', '→', '↓', '⇒', '⇔', '∀', '∂', '∃', '∇', '∈', '∋', '−',
+    '√', '∝', '∞', '∠', '∧', '∨', '∩', '∪', '∫', '∬', '∴', '∵', '∽', '≒', '≠', '≡', '≦', '≧', '≪',
+    '≫', '⊂', '⊃', '⊆', '⊇', '⊥', '⌒', '─', '│', '■', '□', '▲', '△', '▼', '▽', '◆', '◇', '○', '◎',
+    '●', '◯', '★', '☆', '♀', '♂', '♪', '♭',
+];
+
+/// Returns true for the characters of `KEYBOARD_SYMBOLS` and the Wii's own private use glyphs
+pub(crate) fn is_keyboard_symbol(c: char) -> bool {
+    KEYBOARD_SYMBOLS.contains(&c) || glyph_to_unicode(c).is_some()
+}
+
+/// Returns the Unicode equivalent of a Wii private use glyph, or None if the character isn't one
+pub fn glyph_to_unicode(c: char) -> Option<char> {
+    GLYPH_TABLE
+        .iter()
+        .find(|(glyph, _)| *glyph == c)
+        .map(|(_, unicode)| *unicode)
+}
+
+/// Replaces every Wii glyph with its Unicode equivalent. Unknown private use characters become U+FFFD
+/// and control characters are dropped, so the result is always safe to display.
+pub fn to_display_string(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_control())
+        .map(|c| match glyph_to_unicode(c) {
+            Some(unicode) => unicode,
+            None if ('\u{E000}'..='\u{F8FF}').contains(&c) => char::REPLACEMENT_CHARACTER,
+            None => c,
+        })
+        .collect()
+}
+
+/// Turns a name into something every common filesystem accepts as a file name, never empty.
+/// Control characters become `_` like reserved symbols, and Windows device names such as `CON`
+/// get a `_` appended.
+pub fn to_filename_safe_string(name: &str) -> String {
+    // `to_display_string` would drop control characters, which could merge a name into another
+    let name: String = name
+        .chars()
+        .map(|c| if c <= '\u{1F}' { '_' } else { c })
+        .collect();
+    let name: String = to_display_string(&name)
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' | char::REPLACEMENT_CHARACTER => {
+                '_'
+            }
+            c => c,
+        })
+        .collect();
+
+    // Windows doesn't allow names ending in a dot or space
+    let name = name.trim_end_matches(['.', ' ']).trim_start();
+
+    if name.is_empty() {
+        // Same fallback the game uses for ghosts without a name
+        return String::from("Player");
+    }
+
+    let mut name = String::from(name);
+    let stem_length = name.find('.').unwrap_or(name.len());
+    if is_windows_device_name(name[..stem_length].trim_end()) {
+        name.insert(stem_length, '_');
+    }
+    name
+}
+
+/// Names Windows reserves for devices, whatever their case or extension (`con.rkg` included)
+fn is_windows_device_name(stem: &str) -> bool {
+    let stem = stem.to_ascii_uppercase();
+    matches!(
+        stem.as_bytes(),
+        b"CON"
+            | b"PRN"
+            | b"AUX"
+            | b"NUL"
+            | [b'C', b'O', b'M', b'1'..=b'9']
+            | [b'L', b'P', b'T', b'1'..=b'9']
+    )
+}
@@ -1,6 +1,10 @@
-use std::convert::Infallible;
+use core::convert::Infallible;
 
-use crate::byte_handler::{ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::mii::layout,
+    layout::LayoutError,
+};
 
 #[derive(Clone, Copy)]
 pub struct Head {
@@ -19,6 +23,8 @@ pub enum HeadError {
     FaceFeaturesInvalid,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
     #[error("")]
     Infallible(#[from] Infallible),
 }
@@ -42,17 +48,15 @@ impl FromByteHandler for Head {
         T: TryInto<crate::byte_handler::ByteHandler>,
         Self::Err: From<T::Error>,
     {
-        let mut handler = handler.try_into()?;
-        handler.shift_right(5);
-        let shape_value = handler.copy_byte(0);
-        handler.shift_right(1);
-        let byte = handler.copy_byte(1);
+        let handler = handler.try_into()?;
+        let field = |field| handler.read_field(field, 0x20).map(|value| value as u8);
 
         Ok(Head {
-            shape: HeadShape::try_from(shape_value).map_err(|_| HeadError::ShapeInvalid)?,
-            skin_tone: SkinTone::try_from((byte >> 4) & 0x07)
+            shape: HeadShape::try_from(field(layout::HEAD_SHAPE)?)
+                .map_err(|_| HeadError::ShapeInvalid)?,
+            skin_tone: SkinTone::try_from(field(layout::SKIN_TONE)?)
                 .map_err(|_| HeadError::SkinToneInvalid)?,
-            face_features: FaceFeatures::try_from(byte & 0x0F)
+            face_features: FaceFeatures::try_from(field(layout::FACE_FEATURES)?)
                 .map_err(|_| HeadError::FaceFeaturesInvalid)?,
         })
     }
@@ -87,6 +91,21 @@ impl TryFrom<u8> for HeadShape {
     }
 }
 
+impl From<HeadShape> for u8 {
+    fn from(value: HeadShape) -> Self {
+        match value {
+            HeadShape::Sharp => 0x00,
+            HeadShape::Rounded => 0x01,
+            HeadShape::SharpRoundedSmall => 0x02,
+            HeadShape::Large => 0x03,
+            HeadShape::SharpSmall => 0x04,
+            HeadShape::Flat => 0x05,
+            HeadShape::Angular => 0x06,
+            HeadShape::FlatRounded => 0x07,
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum SkinTone {
     Beige,
@@ -112,6 +131,19 @@ impl TryFrom<u8> for SkinTone {
     }
 }
 
+impl From<SkinTone> for u8 {
+    fn from(value: SkinTone) -> Self {
+        match value {
+            SkinTone::Beige => 0x00,
+            SkinTone::Natural => 0x01,
+            SkinTone::WarmIvory => 0x02,
+            SkinTone::Ivory => 0x03,
+            SkinTone::Honey => 0x04,
+            SkinTone::Chestnut => 0x05,
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum FaceFeatures {
     None,
@@ -148,3 +180,44 @@ impl TryFrom<u8> for FaceFeatures {
         }
     }
 }
+
+impl From<FaceFeatures> for u8 {
+    fn from(value: FaceFeatures) -> Self {
+        match value {
+            FaceFeatures::None => 0x00,
+            FaceFeatures::CheekPorcelain => 0x01,
+            FaceFeatures::CheekPorcelainEyeShadowBlue => 0x02,
+            FaceFeatures::Freckles => 0x03,
+            FaceFeatures::UnderTheEyes => 0x04,
+            FaceFeatures::FacialPain => 0x05,
+            FaceFeatures::Cheeks => 0x06,
+            FaceFeatures::Chin => 0x07,
+            FaceFeatures::BrowDroop => 0x08,
+            FaceFeatures::LionsManeBeard => 0x09,
+            FaceFeatures::MouthFrown => 0x0A,
+            FaceFeatures::FoldsCrowsFrown => 0x0B,
+        }
+    }
+}
+
+impl FieldCodec for Head {
+    const SIZE: usize = 2;
+    type Error = HeadError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::HEAD_SHAPE
+            .relative_to(0x20)
+            .write(bytes, u8::from(self.shape) as u32)?;
+        layout::SKIN_TONE
+            .relative_to(0x20)
+            .write(bytes, u8::from(self.skin_tone) as u32)?;
+        layout::FACE_FEATURES
+            .relative_to(0x20)
+            .write(bytes, u8::from(self.face_features) as u32)?;
+        Ok(())
+    }
+}
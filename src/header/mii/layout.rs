@@ -0,0 +1,65 @@
+//! Where every value of the 0x4A byte Mii block lives, offsets are from the start of the Mii
+//! http://wiibrew.org/wiki/Mii_Data#Mii_format
+
+use crate::layout::bit_layout;
+
+/// UTF-16BE, 10 characters
+pub const NAME_OFFSET: usize = 0x02;
+pub const CREATOR_NAME_OFFSET: usize = 0x36;
+pub const NAME_SIZE: usize = 0x14;
+
+bit_layout! {
+    /// Every packed field of the Mii, in order. Names are UTF-16 and not listed, see `NAME_OFFSET`
+    MII_FIELDS {
+        IS_GIRL: 0x00, 1, 1;
+        BIRTHDAY_MONTH: 0x00, 2, 4;
+        BIRTHDAY_DAY: 0x00, 6, 5;
+        FAVORITE_COLOR: 0x01, 3, 4;
+        /// Cleared on favorite Miis
+        FAVORITE_FLAG: 0x01, 7, 1;
+        HEIGHT: 0x16, 0, 8;
+        WEIGHT: 0x17, 0, 8;
+        MII_ID: 0x18, 0, 32;
+        SYSTEM_ID: 0x1C, 0, 32;
+        HEAD_SHAPE: 0x20, 0, 3;
+        SKIN_TONE: 0x20, 3, 3;
+        FACE_FEATURES: 0x20, 6, 4;
+        MINGLE_OFF: 0x21, 5, 1;
+        DOWNLOADED: 0x21, 7, 1;
+        HAIR_TYPE: 0x22, 0, 7;
+        HAIR_COLOR: 0x22, 7, 3;
+        HAIR_FLIPPED: 0x23, 2, 1;
+        EYEBROW_TYPE: 0x24, 0, 5;
+        EYEBROW_ROTATION: 0x24, 5, 5;
+        EYEBROW_COLOR: 0x26, 0, 3;
+        EYEBROW_SIZE: 0x26, 3, 4;
+        EYEBROW_Y: 0x26, 7, 5;
+        EYEBROW_X: 0x27, 4, 4;
+        EYE_TYPE: 0x28, 0, 6;
+        EYE_ROTATION: 0x28, 6, 5;
+        EYE_Y: 0x29, 3, 5;
+        EYE_COLOR: 0x2A, 0, 3;
+        EYE_SIZE: 0x2A, 3, 4;
+        EYE_X: 0x2A, 7, 4;
+        NOSE_TYPE: 0x2C, 0, 4;
+        NOSE_SIZE: 0x2C, 4, 4;
+        NOSE_Y: 0x2D, 0, 5;
+        LIPS_TYPE: 0x2E, 0, 5;
+        LIPS_COLOR: 0x2E, 5, 2;
+        LIPS_SIZE: 0x2E, 7, 4;
+        LIPS_Y: 0x2F, 3, 5;
+        GLASSES_TYPE: 0x30, 0, 4;
+        GLASSES_COLOR: 0x30, 4, 3;
+        GLASSES_SIZE: 0x30, 7, 4;
+        GLASSES_Y: 0x31, 3, 5;
+        MUSTACHE_TYPE: 0x32, 0, 2;
+        BEARD_TYPE: 0x32, 2, 2;
+        FACIAL_HAIR_COLOR: 0x32, 4, 3;
+        MUSTACHE_SIZE: 0x32, 7, 4;
+        MUSTACHE_Y: 0x33, 3, 5;
+        HAS_MOLE: 0x34, 0, 1;
+        MOLE_SIZE: 0x34, 1, 4;
+        MOLE_Y: 0x34, 5, 5;
+        MOLE_X: 0x35, 2, 5;
+    }
+}
@@ -1,6 +1,10 @@
-use std::convert::Infallible;
+use core::convert::Infallible;
 
-use crate::byte_handler::{ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::mii::layout,
+    layout::LayoutError,
+};
 
 #[derive(Clone, Copy)]
 pub struct Lips {
@@ -32,15 +36,15 @@ impl FromByteHandler for Lips {
         T: TryInto<crate::byte_handler::ByteHandler>,
         Self::Err: From<T::Error>,
     {
-        let mut handler = handler.try_into()?;
+        let handler = handler.try_into()?;
+        let field = |field| handler.read_field(field, 0x2E).map(|value| value as u8);
 
         let lips_type =
-            LipsType::try_from(handler.copy_byte(0) >> 3).map_err(|_| LipsError::TypeInvalid)?;
-        let y = handler.copy_byte(1) & 0x1F;
-        handler.shift_right(1);
-        let lips_color = LipsColor::try_from(handler.copy_byte(0) & 0x03)
-            .map_err(|_| LipsError::ColorInvalid)?;
-        let size = handler.copy_byte(1) >> 4;
+            LipsType::try_from(field(layout::LIPS_TYPE)?).map_err(|_| LipsError::TypeInvalid)?;
+        let y = field(layout::LIPS_Y)?;
+        let lips_color =
+            LipsColor::try_from(field(layout::LIPS_COLOR)?).map_err(|_| LipsError::ColorInvalid)?;
+        let size = field(layout::LIPS_SIZE)?;
 
         Ok(Self {
             lips_type,
@@ -59,6 +63,8 @@ pub enum LipsError {
     ColorInvalid,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
     #[error("")]
     Infallible(#[from] Infallible),
 }
@@ -81,6 +87,16 @@ impl TryFrom<u8> for LipsColor {
     }
 }
 
+impl From<LipsColor> for u8 {
+    fn from(value: LipsColor) -> Self {
+        match value {
+            LipsColor::Orange => 0,
+            LipsColor::Red => 1,
+            LipsColor::Pink => 2,
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum LipsType {
     Neutral,
@@ -141,3 +157,59 @@ impl TryFrom<u8> for LipsType {
         }
     }
 }
+
+impl From<LipsType> for u8 {
+    fn from(value: LipsType) -> Self {
+        match value {
+            LipsType::Neutral => 0x17,
+            LipsType::NeutralLips => 0x01,
+            LipsType::Smile => 0x13,
+            LipsType::SmileStroke => 0x15,
+            LipsType::SmileTeeth => 0x16,
+            LipsType::LipsSmall => 0x05,
+            LipsType::LipsLarge => 0x00,
+            LipsType::Wave => 0x08,
+            LipsType::WaveAngrySmall => 0x0A,
+            LipsType::NeutralStrokeLarge => 0x10,
+            LipsType::TeethSurprised => 0x06,
+            LipsType::LipsExtraLarge => 0x0D,
+            LipsType::LipsUp => 0x07,
+            LipsType::NeutralDown => 0x09,
+            LipsType::Surprised => 0x02,
+            LipsType::TeethMiddle => 0x11,
+            LipsType::NeutralStroke => 0x03,
+            LipsType::LipsExtraSmall => 0x04,
+            LipsType::Malicious => 0x0F,
+            LipsType::LipsDual => 0x0B,
+            LipsType::NeutralComma => 0x14,
+            LipsType::NeutralUp => 0x12,
+            LipsType::TeethLarge => 0x0E,
+            LipsType::WaveAngry => 0x0C,
+        }
+    }
+}
+
+impl FieldCodec for Lips {
+    const SIZE: usize = 2;
+    type Error = LipsError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::LIPS_TYPE
+            .relative_to(0x2E)
+            .write(bytes, u8::from(self.lips_type) as u32)?;
+        layout::LIPS_COLOR
+            .relative_to(0x2E)
+            .write(bytes, u8::from(self.lips_color) as u32)?;
+        layout::LIPS_SIZE
+            .relative_to(0x2E)
+            .write(bytes, self.size as u32)?;
+        layout::LIPS_Y
+            .relative_to(0x2E)
+            .write(bytes, self.y as u32)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use core::{convert::Infallible, fmt::Display};
+
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+
+use crate::{
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::mii::layout,
+    layout::LayoutError,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum MiiIdError {
+    #[error("ByteHandler Error: {0}")]
+    ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
+    #[error("")]
+    Infallible(#[from] Infallible),
+}
+
+/// Unique identifier given to a Mii when it is created
+/// http://wiibrew.org/wiki/Mii_Data#Mii_ID
+///
+/// The upper 3 bits are type flags, the lower 28 bits count 4 second steps since 2006-01-01 00:00:00
+/// (console time, no timezone).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MiiId {
+    raw: u32,
+}
+
+impl MiiId {
+    #[inline(always)]
+    pub fn new(raw: u32) -> Self {
+        Self { raw }
+    }
+
+    pub fn raw(self) -> u32 {
+        self.raw
+    }
+
+    /// Special Miis (gold pants) are the only ones without the highest bit set
+    pub fn is_special(self) -> bool {
+        self.raw & 0x8000_0000 == 0
+    }
+
+    /// Foreign Miis (blue pants) were made on another console and can't be edited
+    pub fn is_foreign(self) -> bool {
+        self.raw & 0x4000_0000 != 0
+    }
+
+    /// A "normal" Mii is neither special nor foreign, i.e. made on this console in the Mii Channel
+    pub fn is_normal(self) -> bool {
+        !self.is_special() && !self.is_foreign()
+    }
+
+    /// Number of 4 second steps elapsed between 2006-01-01 and the Mii's creation
+    pub fn timestamp(self) -> u32 {
+        self.raw & 0x0FFF_FFFF
+    }
+
+    /// Date and time at which the Mii was created, according to the console's clock
+    pub fn created_at(self) -> NaiveDateTime {
+        let epoch = NaiveDate::from_ymd_opt(2006, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+
+        epoch + TimeDelta::seconds(self.timestamp() as i64 * 4)
+    }
+}
+
+impl From<u32> for MiiId {
+    fn from(value: u32) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<MiiId> for u32 {
+    fn from(value: MiiId) -> Self {
+        value.raw
+    }
+}
+
+impl Display for MiiId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:08X}", self.raw)
+    }
+}
+
+impl FromByteHandler for MiiId {
+    type Err = MiiIdError;
+    /// Expects Mii 0x18..=0x1B
+    fn from_byte_handler<T>(handler: T) -> Result<Self, Self::Err>
+    where
+        T: TryInto<crate::byte_handler::ByteHandler>,
+        Self::Err: From<T::Error>,
+    {
+        Ok(Self::new(
+            handler.try_into()?.read_field(layout::MII_ID, 0x18)?,
+        ))
+    }
+}
+
+impl FieldCodec for MiiId {
+    const SIZE: usize = 4;
+    type Error = MiiIdError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::MII_ID.relative_to(0x18).write(bytes, self.raw())?;
+        Ok(())
+    }
+}
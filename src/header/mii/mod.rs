@@ -1,8 +1,9 @@
 // http://wiibrew.org/wiki/Mii_Data#Mii_format
 
 use crate::{
-    byte_handler::{ByteHandler, ByteHandlerError, FromByteHandler},
+    byte_handler::{ByteHandlerError, FromByteHandler},
     header::mii::{
+        audit::MiiAuditIssue,
         bday::{Birthday, BirthdayError},
         build::{Build, BuildError},
         eyebrows::{Eyebrows, EyebrowsError},
@@ -13,11 +14,16 @@ use crate::{
         hair::{Hair, HairError},
         head::{Head, HeadError},
         lips::{Lips, LipsError},
+        mii_id::{MiiId, MiiIdError},
         mole::{Mole, MoleError},
         nose::{Nose, NoseError},
+        system_id::{SystemId, SystemIdError},
     },
+    layout::LayoutError,
 };
+use alloc::{string::String, vec::Vec};
 
+pub mod audit;
 pub mod bday;
 pub mod build;
 pub mod eyebrows;
@@ -25,16 +31,20 @@ pub mod eyes;
 pub mod facial_hair;
 pub mod fav_color;
 pub mod glasses;
+pub mod glyphs;
 pub mod hair;
 pub mod head;
+pub mod layout;
 pub mod lips;
+pub mod mii_id;
 pub mod mole;
 pub mod nose;
+pub mod system_id;
 
 #[derive(thiserror::Error, Debug)]
 pub enum MiiError {
     #[error("FromUtf16Error: {0}")]
-    FromUtf16Error(#[from] std::string::FromUtf16Error),
+    FromUtf16Error(#[from] alloc::string::FromUtf16Error),
     #[error("Invalid data length")]
     InvalidLength,
     #[error("Birthday Error: {0}")]
@@ -61,10 +71,17 @@ pub enum MiiError {
     FacialHairError(#[from] FacialHairError),
     #[error("Mole Error: {0}")]
     MoleError(#[from] MoleError),
+    #[error("Mii ID Error: {0}")]
+    MiiIdError(#[from] MiiIdError),
+    #[error("System ID Error: {0}")]
+    SystemIdError(#[from] SystemIdError),
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
 }
 
+#[derive(Clone)]
 pub struct Mii {
     is_girl: bool,
     birthday: Birthday,
@@ -72,8 +89,8 @@ pub struct Mii {
     is_favorite: bool,
     name: String,
     build: Build,
-    mii_id: u32,
-    system_id: u32,
+    mii_id: MiiId,
+    system_id: SystemId,
     head: Head,
     mingle_off: bool,
     downloaded: bool,
@@ -86,30 +103,29 @@ pub struct Mii {
     facial_hair: FacialHair,
     mole: Mole,
     creator_name: String,
+    bytes: [u8; 0x4A],
 }
 
 impl Mii {
     pub fn new(mii_data: impl TryInto<[u8; 0x4A]>) -> Result<Self, MiiError> {
         let mii_data = mii_data.try_into().map_err(|_| MiiError::InvalidLength)?;
 
-        let bytes = ByteHandler::try_from(&mii_data[0..=1])?;
-        let is_girl = bytes.read_bool(6);
-        let birthday = Birthday::from_byte_handler(bytes)?;
+        let is_girl = layout::IS_GIRL.read_bool(&mii_data)?;
+        let birthday = Birthday::from_byte_handler(&mii_data[0..=1])?;
 
         let favorite_color = FavColor::from_byte_handler(mii_data[1])?;
-        let is_favorite = mii_data[1].is_multiple_of(2);
+        let is_favorite = !layout::FAVORITE_FLAG.read_bool(&mii_data)?;
 
         let name = utf16be_to_string(&mii_data[0x02..=0x15])?;
 
         let build = Build::from_byte_handler(&mii_data[0x16..=0x17])?;
 
-        let mii_id = ByteHandler::try_from(&mii_data[0x18..=0x1B])?.copy_dword();
-        let system_id = ByteHandler::try_from(&mii_data[0x1C..=0x1F])?.copy_dword();
+        let mii_id = MiiId::from_byte_handler(&mii_data[0x18..=0x1B])?;
+        let system_id = SystemId::from_byte_handler(&mii_data[0x1C..=0x1F])?;
 
-        let bytes = ByteHandler::try_from(&mii_data[0x20..=0x21])?;
-        let mingle_off = bytes.read_bool(2);
-        let downloaded = bytes.read_bool(0);
-        let head = Head::from_byte_handler(bytes)?;
+        let mingle_off = layout::MINGLE_OFF.read_bool(&mii_data)?;
+        let downloaded = layout::DOWNLOADED.read_bool(&mii_data)?;
+        let head = Head::from_byte_handler(&mii_data[0x20..=0x21])?;
         let hair = Hair::from_byte_handler(&mii_data[0x22..=0x23])?;
         let eyebrows = Eyebrows::from_byte_handler(&mii_data[0x24..=0x27])?;
         let eyes = Eyes::from_byte_handler(&mii_data[0x28..=0x2B])?;
@@ -142,9 +158,15 @@ impl Mii {
             facial_hair,
             mole,
             creator_name,
+            bytes: mii_data,
         })
     }
 
+    /// Reports every value a real Mii Channel could never have produced, an empty list means the Mii looks legitimate
+    pub fn audit(&self) -> Vec<MiiAuditIssue> {
+        audit::audit(self)
+    }
+
     pub fn is_girl(&self) -> bool {
         self.is_girl
     }
@@ -165,15 +187,25 @@ impl Mii {
         &self.name
     }
 
+    /// Name with Wii-specific glyphs mapped to Unicode, safe to render outside of the Wii
+    pub fn display_name(&self) -> String {
+        glyphs::to_display_string(&self.name)
+    }
+
+    /// Display name with characters that aren't allowed in file names replaced by underscores
+    pub fn filename_safe_name(&self) -> String {
+        glyphs::to_filename_safe_string(&self.name)
+    }
+
     pub fn build(&self) -> Build {
         self.build
     }
 
-    pub fn mii_id(&self) -> u32 {
+    pub fn mii_id(&self) -> MiiId {
         self.mii_id
     }
 
-    pub fn system_id(&self) -> u32 {
+    pub fn system_id(&self) -> SystemId {
         self.system_id
     }
 
@@ -226,7 +258,7 @@ impl Mii {
     }
 }
 
-fn utf16be_to_string(bytes: &[u8]) -> Result<String, std::string::FromUtf16Error> {
+pub(crate) fn utf16be_to_string(bytes: &[u8]) -> Result<String, alloc::string::FromUtf16Error> {
     let utf16: Vec<u16> = bytes
         .chunks_exact(2)
         .map(|c| u16::from_be_bytes([c[0], c[1]]))
@@ -1,6 +1,10 @@
-use std::convert::Infallible;
+use core::convert::Infallible;
 
-use crate::byte_handler::{ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::mii::layout,
+    layout::LayoutError,
+};
 
 #[derive(Clone, Copy)]
 pub struct Mole {
@@ -30,13 +34,13 @@ impl FromByteHandler for Mole {
         T: TryInto<crate::byte_handler::ByteHandler>,
         Self::Err: From<T::Error>,
     {
-        let mut handler = handler.try_into()?;
-        let has_mole = handler.read_bool(15);
-        handler.shift_right(1);
-        let x = handler.copy_byte(1) & 0x1F;
-        handler.shift_right(2);
-        let y = handler.copy_byte(1) >> 3;
-        let size = handler.copy_byte(0) & 0x0F;
+        let handler = handler.try_into()?;
+        let field = |field| handler.read_field(field, 0x34).map(|value| value as u8);
+
+        let has_mole = field(layout::HAS_MOLE)? != 0;
+        let x = field(layout::MOLE_X)?;
+        let y = field(layout::MOLE_Y)?;
+        let size = field(layout::MOLE_SIZE)?;
         Ok(Self {
             has_mole,
             x,
@@ -50,6 +54,33 @@ impl FromByteHandler for Mole {
 pub enum MoleError {
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
     #[error("")]
     Infallible(#[from] Infallible),
 }
+
+impl FieldCodec for Mole {
+    const SIZE: usize = 2;
+    type Error = MoleError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::HAS_MOLE
+            .relative_to(0x34)
+            .write(bytes, self.has_mole as u32)?;
+        layout::MOLE_SIZE
+            .relative_to(0x34)
+            .write(bytes, self.size as u32)?;
+        layout::MOLE_Y
+            .relative_to(0x34)
+            .write(bytes, self.y as u32)?;
+        layout::MOLE_X
+            .relative_to(0x34)
+            .write(bytes, self.x as u32)?;
+        Ok(())
+    }
+}
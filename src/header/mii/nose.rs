@@ -1,6 +1,10 @@
-use std::convert::Infallible;
+use core::convert::Infallible;
 
-use crate::byte_handler::{ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::mii::layout,
+    layout::LayoutError,
+};
 
 #[derive(Clone, Copy)]
 pub struct Nose {
@@ -29,11 +33,13 @@ impl FromByteHandler for Nose {
         Self::Err: From<T::Error>,
     {
         let handler = handler.try_into()?;
+        let field = |field| handler.read_field(field, 0x2C).map(|value| value as u8);
+
         Ok(Self {
-            nose_type: NoseType::try_from(handler.copy_byte(0) >> 4)
+            nose_type: NoseType::try_from(field(layout::NOSE_TYPE)?)
                 .map_err(|_| NoseError::TypeInvalid)?,
-            size: handler.copy_byte(0) & 0x0F,
-            y: handler.copy_byte(1) >> 3,
+            size: field(layout::NOSE_SIZE)?,
+            y: field(layout::NOSE_Y)?,
         })
     }
 }
@@ -44,6 +50,8 @@ pub enum NoseError {
     TypeInvalid,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
     #[error("")]
     Infallible(#[from] Infallible),
 }
@@ -84,3 +92,44 @@ impl TryFrom<u8> for NoseType {
         }
     }
 }
+
+impl From<NoseType> for u8 {
+    fn from(value: NoseType) -> Self {
+        match value {
+            NoseType::Normal => 0x01,
+            NoseType::Rounded => 0x0A,
+            NoseType::Dot => 0x02,
+            NoseType::Arrow => 0x03,
+            NoseType::Roman => 0x06,
+            NoseType::Triangle => 0x00,
+            NoseType::Button => 0x05,
+            NoseType::RoundedInverted => 0x04,
+            NoseType::Potato => 0x08,
+            NoseType::Grecian => 0x09,
+            NoseType::Snub => 0x07,
+            NoseType::Aquiline => 0x0B,
+        }
+    }
+}
+
+impl FieldCodec for Nose {
+    const SIZE: usize = 2;
+    type Error = NoseError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::NOSE_TYPE
+            .relative_to(0x2C)
+            .write(bytes, u8::from(self.nose_type) as u32)?;
+        layout::NOSE_SIZE
+            .relative_to(0x2C)
+            .write(bytes, self.size as u32)?;
+        layout::NOSE_Y
+            .relative_to(0x2C)
+            .write(bytes, self.y as u32)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use core::{convert::Infallible, fmt::Display};
+
+use crate::{
+    byte_handler::{ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::mii::layout,
+    layout::LayoutError,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum SystemIdError {
+    #[error("ByteHandler Error: {0}")]
+    ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
+    #[error("")]
+    Infallible(#[from] Infallible),
+}
+
+/// Fingerprint of the console a Mii was created on, derived from the Wii's MAC address
+/// http://wiibrew.org/wiki/Mii_Data#Mii_format
+///
+/// Byte 0 is the 8-bit checksum (wrapping sum) of the first 3 bytes of the MAC address,
+/// bytes 1..=3 are the last 3 bytes of the MAC address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SystemId {
+    raw: u32,
+}
+
+impl SystemId {
+    #[inline(always)]
+    pub fn new(raw: u32) -> Self {
+        Self { raw }
+    }
+
+    /// Builds the System ID a console with this MAC address would write into its Miis
+    pub fn from_mac_address(mac: [u8; 6]) -> Self {
+        let checksum = mac[0].wrapping_add(mac[1]).wrapping_add(mac[2]);
+        Self::new(u32::from_be_bytes([checksum, mac[3], mac[4], mac[5]]))
+    }
+
+    pub fn raw(self) -> u32 {
+        self.raw
+    }
+
+    /// Checksum of the manufacturer (OUI) half of the MAC address
+    pub fn mac_checksum(self) -> u8 {
+        self.raw.to_be_bytes()[0]
+    }
+
+    /// Last 3 bytes of the console's MAC address
+    pub fn mac_suffix(self) -> [u8; 3] {
+        let bytes = self.raw.to_be_bytes();
+        [bytes[1], bytes[2], bytes[3]]
+    }
+
+    /// Returns true if a console with this MAC address could have produced this System ID
+    pub fn matches_mac_address(self, mac: [u8; 6]) -> bool {
+        Self::from_mac_address(mac) == self
+    }
+}
+
+impl From<u32> for SystemId {
+    fn from(value: u32) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<SystemId> for u32 {
+    fn from(value: SystemId) -> Self {
+        value.raw
+    }
+}
+
+impl Display for SystemId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:08X}", self.raw)
+    }
+}
+
+impl FromByteHandler for SystemId {
+    type Err = SystemIdError;
+    /// Expects Mii 0x1C..=0x1F
+    fn from_byte_handler<T>(handler: T) -> Result<Self, Self::Err>
+    where
+        T: TryInto<crate::byte_handler::ByteHandler>,
+        Self::Err: From<T::Error>,
+    {
+        Ok(Self::new(
+            handler.try_into()?.read_field(layout::SYSTEM_ID, 0x1C)?,
+        ))
+    }
+}
+
+impl FieldCodec for SystemId {
+    const SIZE: usize = 4;
+    type Error = SystemIdError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::SYSTEM_ID
+            .relative_to(0x1C)
+            .write(bytes, self.raw())?;
+        Ok(())
+    }
+}
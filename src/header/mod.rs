@@ -1,7 +1,8 @@
 use crate::{
-    byte_handler::{ByteHandler, ByteHandlerError, FromByteHandler},
+    byte_handler::ByteHandlerError,
+    crc::crc16,
     header::{
-        combo::{Combo, ComboError},
+        combo::{Character, Combo, ComboError, ComboIssue, ComboValidation, Vehicle},
         controller::{Controller, ControllerError},
         date::{Date, DateError},
         ghost_type::{GhostType, GhostTypeError},
@@ -10,15 +11,19 @@ use crate::{
         mii::{Mii, MiiError},
         slot_id::{SlotId, SlotIdError},
     },
+    layout::{BitField, LayoutError},
 };
+use alloc::vec::Vec;
 
-use std::io::Read;
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
 
 pub mod combo;
 pub mod controller;
 pub mod date;
 pub mod ghost_type;
 pub mod in_game_time;
+pub mod layout;
 pub mod location;
 pub mod mii;
 pub mod slot_id;
@@ -43,20 +48,28 @@ pub enum HeaderError {
     GhostTypeError(#[from] GhostTypeError),
     #[error("Mii Error: {0}")]
     MiiError(#[from] MiiError),
+    #[cfg(feature = "std")]
     #[error("Io Error: {0}")]
     IoError(#[from] std::io::Error),
     #[error("Country Error: {0}")]
     CountryError(#[from] CountryError),
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
 }
 
+/// Bits of 0x0C..=0x0D that are neither the compressed flag, ghost type nor automatic drift flag
+const UNKNOWN_FLAGS_MASK: u16 = 0xF601;
+
 /// All the data in the Header of an RKGD
 /// https://wiki.tockdom.com/wiki/RKG_(File_Format)#File_Header
+#[derive(Clone)]
 pub struct Header {
     finish_time: InGameTime,
     slot_id: SlotId,
     combo: Combo,
+    combo_validation: ComboValidation,
     date_set: Date,
     controller: Controller,
     is_compressed: bool,
@@ -71,18 +84,42 @@ pub struct Header {
     mii_bytes: [u8; 0x4A],
     mii: Mii,
     mii_crc16: u16,
+    /// Header as read, keeps the bits none of the fields above cover
+    bytes: [u8; 0x88],
 }
 
 impl Header {
     /// Reads header from a file at the path
+    #[cfg(feature = "std")]
     pub fn new_from_path<P: AsRef<std::path::Path>>(p: P) -> Result<Self, HeaderError> {
+        Self::read_from(std::fs::File::open(p)?)
+    }
+
+    /// Reads the 0x88 bytes of a header and nothing more, leaving the reader at the input data
+    #[cfg(feature = "std")]
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, HeaderError> {
         let mut rkg_data = [0u8; 0x88];
-        std::fs::File::open(p)?.read_exact(&mut rkg_data)?;
+        reader.read_exact(&mut rkg_data)?;
         Self::new(&rkg_data)
     }
 
-    /// Reads header from slice
+    /// Writes the header as `to_bytes` packs it
+    #[cfg(feature = "std")]
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), HeaderError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Reads header from slice, rejecting impossible combos
     pub fn new(header_data: &[u8]) -> Result<Self, HeaderError> {
+        Self::new_with_combo_validation(header_data, ComboValidation::Strict)
+    }
+
+    /// Reads header from slice, checking the combo as strictly as `combo_validation` asks for
+    pub fn new_with_combo_validation(
+        header_data: &[u8],
+        combo_validation: ComboValidation,
+    ) -> Result<Self, HeaderError> {
         if header_data.len() != 0x88 {
             return Err(HeaderError::NotCorrectSize);
         }
@@ -90,42 +127,55 @@ impl Header {
             return Err(HeaderError::NotRKGD);
         }
 
-        let finish_time = InGameTime::from_byte_handler(&header_data[4..7])?;
-        let slot_id = SlotId::from_byte_handler(header_data[7])?;
-        let combo = Combo::from_byte_handler(&header_data[0x08..0x0A])?;
-        let date_set = Date::from_byte_handler(&header_data[0x09..=0x0B])?;
-        let controller = Controller::from_byte_handler(header_data[0x0B])?;
-        let is_compressed = ByteHandler::from(header_data[0x0C]).read_bool(3);
-        let ghost_type = GhostType::from_byte_handler(&header_data[0x0C..=0x0D])?;
-        let is_automatic_drift = ByteHandler::from(header_data[0x0D]).read_bool(1);
+        let finish_time = read_in_game_time(header_data, 0)?;
+        let slot_id = SlotId::try_from(layout::SLOT_ID.read(header_data)? as u8)?;
+        let combo = Combo::new_with_validation(
+            Vehicle::try_from(layout::VEHICLE.read(header_data)? as u8)
+                .map_err(|_| ComboError::InvalidVehicleId)?,
+            Character::try_from(layout::CHARACTER.read(header_data)? as u8)
+                .map_err(|_| ComboError::InvalidCharacterId)?,
+            combo_validation,
+        )?;
+        let date_set = Date::new(
+            layout::YEAR.read(header_data)? as u16 + 2000,
+            layout::MONTH.read(header_data)? as u8,
+            layout::DAY.read(header_data)? as u8,
+        )?;
+        let controller = Controller::try_from(layout::CONTROLLER.read(header_data)? as u8)?;
+        let is_compressed = layout::IS_COMPRESSED.read_bool(header_data)?;
+        let ghost_type = GhostType::try_from(layout::GHOST_TYPE.read(header_data)? as u8)?;
+        let is_automatic_drift = layout::IS_AUTOMATIC_DRIFT.read_bool(header_data)?;
         let decompressed_input_data_length =
-            ByteHandler::try_from(&header_data[0x0E..=0x0F])?.copy_word(0);
-
-        let lap_count = header_data[0x10];
-        let mut lap_split_times: [InGameTime; 10] = [Default::default(); 10];
-        for index in 0..10 {
-            let start = (0x11 + index * 3) as usize;
-            lap_split_times[index as usize] =
-                InGameTime::from_byte_handler(&header_data[start..start + 3])?;
+            layout::DECOMPRESSED_INPUT_DATA_LENGTH.read(header_data)? as u16;
+
+        let lap_count = layout::LAP_COUNT.read(header_data)? as u8;
+        let mut lap_split_times: [InGameTime; layout::LAP_SPLIT_COUNT] = Default::default();
+        for (lap, lap_split_time) in lap_split_times.iter_mut().enumerate() {
+            *lap_split_time = read_in_game_time(header_data, lap + 1)?;
         }
 
-        let codes = ByteHandler::try_from(&header_data[0x34..=0x37]).unwrap();
-        let country = Country::NotSet; // Country::try_from(codes.copy_byte(0))?;
-        let subregion = codes.copy_byte(1);
-        let location_code = codes.copy_word(1);
+        // Unknown IDs stay readable through `country_id`
+        let country =
+            Country::try_from(layout::COUNTRY.read(header_data)? as u8).unwrap_or(Country::NotSet);
+        let subregion = layout::SUBREGION.read(header_data)? as u8;
+        let location_code = layout::LOCATION_CODE.read(header_data)? as u16;
 
-        let mut mii_bytes = [0_u8; 0x4A];
-        for (index, byte) in header_data[0x3C..0x3C + 0x4A].iter().enumerate() {
-            mii_bytes[index] = *byte;
-        }
+        let mut mii_bytes = [0_u8; layout::MII_SIZE];
+        mii_bytes.copy_from_slice(
+            &header_data[layout::MII_OFFSET..layout::MII_OFFSET + layout::MII_SIZE],
+        );
         let mii = Mii::new(mii_bytes)?;
 
-        let mii_crc16 = ByteHandler::try_from(&header_data[0x86..=0x87])?.copy_word(0);
+        let mii_crc16 = layout::MII_CRC16.read(header_data)? as u16;
+
+        let mut bytes = [0_u8; 0x88];
+        bytes.copy_from_slice(header_data);
 
         Ok(Self {
             finish_time,
             slot_id,
             combo,
+            combo_validation,
             date_set,
             controller,
             is_compressed,
@@ -140,9 +190,41 @@ impl Header {
             mii_bytes,
             mii,
             mii_crc16,
+            bytes,
         })
     }
 
+    /// Packs every field back into the 0x88 bytes of a header. Bits no field covers are kept
+    /// as they were read, so an unmodified header is reproduced exactly.
+    pub fn to_bytes(&self) -> Result<[u8; 0x88], HeaderError> {
+        let mut bytes = self.bytes;
+
+        write_in_game_time(&mut bytes, 0, self.finish_time)?;
+        layout::SLOT_ID.write(&mut bytes, u8::from(self.slot_id) as u32)?;
+        layout::VEHICLE.write(&mut bytes, u8::from(self.combo.vehicle()) as u32)?;
+        layout::CHARACTER.write(&mut bytes, u8::from(self.combo.character()) as u32)?;
+        layout::YEAR.write(&mut bytes, (self.date_set.year() - 2000) as u32)?;
+        layout::MONTH.write(&mut bytes, self.date_set.month() as u32)?;
+        layout::DAY.write(&mut bytes, self.date_set.day() as u32)?;
+        layout::CONTROLLER.write(&mut bytes, u8::from(self.controller) as u32)?;
+        layout::IS_COMPRESSED.write_bool(&mut bytes, self.is_compressed)?;
+        layout::GHOST_TYPE.write(&mut bytes, u8::try_from(self.ghost_type)? as u32)?;
+        layout::IS_AUTOMATIC_DRIFT.write_bool(&mut bytes, self.is_automatic_drift)?;
+        layout::DECOMPRESSED_INPUT_DATA_LENGTH
+            .write(&mut bytes, self.decompressed_input_data_length as u32)?;
+        layout::LAP_COUNT.write(&mut bytes, self.lap_count as u32)?;
+        for (lap, lap_split_time) in self.lap_split_times.iter().enumerate() {
+            write_in_game_time(&mut bytes, lap + 1, *lap_split_time)?;
+        }
+        layout::SUBREGION.write(&mut bytes, self.subregion as u32)?;
+        layout::LOCATION_CODE.write(&mut bytes, self.location_code as u32)?;
+        bytes[layout::MII_OFFSET..layout::MII_OFFSET + layout::MII_SIZE]
+            .copy_from_slice(&self.mii_bytes);
+        layout::MII_CRC16.write(&mut bytes, self.mii_crc16 as u32)?;
+
+        Ok(bytes)
+    }
+
     /// Returns true if Mii CRC16 is correct (i.e. Mii data not illegally tampered with)
     pub fn verify_mii_crc16(&self) -> bool {
         crc16(&self.mii_bytes) == self.mii_crc16()
@@ -173,6 +255,16 @@ impl Header {
         &self.combo
     }
 
+    /// Problems with the combo and its Mii outfit, always empty with `ComboValidation::Permissive`
+    pub fn combo_issues(&self) -> Vec<ComboIssue> {
+        match self.combo_validation {
+            ComboValidation::Permissive => Vec::new(),
+            ComboValidation::Strict | ComboValidation::Warn => {
+                self.combo.issues_with_mii(&self.mii)
+            }
+        }
+    }
+
     pub fn set_combo(&mut self, combo: Combo) {
         self.combo = combo;
     }
@@ -209,6 +301,11 @@ impl Header {
         self.is_automatic_drift
     }
 
+    /// Undocumented bits of 0x0C..=0x0D, in place (mask 0xF601)
+    pub fn unknown_flags(&self) -> u16 {
+        u16::from_be_bytes([self.bytes[0x0C], self.bytes[0x0D]]) & UNKNOWN_FLAGS_MASK
+    }
+
     pub fn decompressed_input_data_length(&self) -> u16 {
         self.decompressed_input_data_length
     }
@@ -217,18 +314,25 @@ impl Header {
         self.lap_count
     }
 
+    /// Split times of the laps actually driven. Only 10 are stored, so a larger lap count is cut off.
     pub fn lap_split_times(&self) -> &[InGameTime] {
-        &self.lap_split_times[0..self.lap_count as usize]
+        &self.lap_split_times[..(self.lap_count as usize).min(layout::LAP_SPLIT_COUNT)]
     }
 
     pub fn set_lap_split_times(&mut self, lap_split_times: [InGameTime; 10]) {
         self.lap_split_times = lap_split_times;
     }
 
+    /// `Country::NotSet` if unset or not a country of the unmodded Wii, see `country_id` for the stored value
     pub fn country(&self) -> Country {
         self.country
     }
 
+    /// Country ID as stored at 0x34, 0xFF if not set
+    pub fn country_id(&self) -> u8 {
+        self.bytes[layout::COUNTRY.offset()]
+    }
+
     pub fn subregion(&self) -> u8 {
         self.subregion
     }
@@ -237,6 +341,11 @@ impl Header {
         self.location_code
     }
 
+    /// Raw 0x34..=0x3B, including the 4 undocumented bytes after the location code
+    pub fn location_bytes(&self) -> &[u8] {
+        &self.bytes[0x34..0x3C]
+    }
+
     pub fn mii(&self) -> &Mii {
         &self.mii
     }
@@ -246,20 +355,40 @@ impl Header {
     }
 }
 
-fn crc16(value: &[u8]) -> u16 {
-    let mut crc: u16 = 0x0000; // Initial value for XModem variant
-    let polynomial: u16 = 0x1021; // Standard CCITT polynomial
+/// Reads the finish time for `index` 0 and the split of lap `index - 1` otherwise
+fn read_in_game_time(header_data: &[u8], index: usize) -> Result<InGameTime, HeaderError> {
+    let (minutes, seconds, milliseconds) = in_game_time_fields(index);
 
-    for &byte in value.iter() {
-        crc ^= (byte as u16) << 8; // XOR current byte with the high byte of CRC
+    Ok(InGameTime::new(
+        minutes.read(header_data)? as u8,
+        seconds.read(header_data)? as u8,
+        milliseconds.read(header_data)? as u16,
+    ))
+}
 
-        for _ in 0..8 {
-            if crc & 0x8000 != 0 {
-                crc = (crc << 1) ^ polynomial;
-            } else {
-                crc <<= 1;
-            }
+fn write_in_game_time(bytes: &mut [u8], index: usize, time: InGameTime) -> Result<(), HeaderError> {
+    let (minutes, seconds, milliseconds) = in_game_time_fields(index);
+
+    minutes.write(bytes, time.minutes() as u32)?;
+    seconds.write(bytes, time.seconds() as u32)?;
+    milliseconds.write(bytes, time.milliseconds() as u32)?;
+    Ok(())
+}
+
+fn in_game_time_fields(index: usize) -> (BitField, BitField, BitField) {
+    match index {
+        0 => (
+            layout::FINISH_MINUTES,
+            layout::FINISH_SECONDS,
+            layout::FINISH_MILLISECONDS,
+        ),
+        lap => {
+            let offset = (lap - 1) * layout::LAP_SPLIT_STRIDE;
+            (
+                layout::LAP_MINUTES.offset_by(offset),
+                layout::LAP_SECONDS.offset_by(offset),
+                layout::LAP_MILLISECONDS.offset_by(offset),
+            )
         }
     }
-    crc
 }
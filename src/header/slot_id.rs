@@ -1,8 +1,12 @@
 // https://wiki.tockdom.com/wiki/Slot
 
-use std::convert::Infallible;
+use core::convert::Infallible;
 
-use crate::byte_handler::{ByteHandler, ByteHandlerError, FromByteHandler};
+use crate::{
+    byte_handler::{ByteHandler, ByteHandlerError, FieldCodec, FromByteHandler, field_bytes},
+    header::layout,
+    layout::LayoutError,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum SlotIdError {
@@ -10,11 +14,13 @@ pub enum SlotIdError {
     NonExistentSlotId,
     #[error("ByteHandler Error: {0}")]
     ByteHandlerError(#[from] ByteHandlerError),
+    #[error("Layout Error: {0}")]
+    LayoutError(#[from] LayoutError),
     #[error("")]
     Infallible(#[from] Infallible),
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum SlotId {
     // Normal Tracks
     LuigiCircuit,
@@ -188,3 +194,19 @@ impl FromByteHandler for SlotId {
         (handler.try_into()?.copy_byte(0) >> 2).try_into()
     }
 }
+
+impl FieldCodec for SlotId {
+    const SIZE: usize = 1;
+    type Error = SlotIdError;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_byte_handler(field_bytes::<Self>(bytes)?)
+    }
+
+    fn encode(&self, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        layout::SLOT_ID
+            .relative_to(0x07)
+            .write(bytes, u8::from(*self) as u32)?;
+        Ok(())
+    }
+}
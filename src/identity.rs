@@ -0,0 +1,441 @@
+//! Groups ghosts by the player who drove them. Mii names change too often to go by, so ghosts are
+//! linked through what's stable: the CTGP player ID and the Mii's own IDs. The creator name and
+//! location of a Mii are shared by unrelated players often enough that they only suggest merges.
+
+use alloc::{
+    collections::{BTreeMap, VecDeque, btree_map::Entry},
+    string::String,
+    vec,
+    vec::Vec,
+};
+use core::fmt::Display;
+
+use crate::{
+    Ghost,
+    header::mii::{mii_id::MiiId, system_id::SystemId},
+};
+
+/// Why two ghosts are thought to come from the same player
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LinkReason {
+    /// Both have a CTGP footer with this player ID
+    PlayerId(u64),
+    /// Both were driven with the same Mii, whose IDs can't collide across consoles
+    Mii { system_id: SystemId, mii_id: MiiId },
+    /// Both Miis were made by someone with this name, in the same country and subregion
+    CreatorAndLocation {
+        creator_name: String,
+        country_id: u8,
+        subregion: u8,
+    },
+}
+
+impl LinkReason {
+    /// Strong links put ghosts in the same identity, weak ones only suggest merging identities
+    pub fn is_strong(&self) -> bool {
+        !matches!(self, Self::CreatorAndLocation { .. })
+    }
+}
+
+impl Display for LinkReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::PlayerId(player_id) => write!(f, "same CTGP player ID {player_id:016X}"),
+            Self::Mii { system_id, mii_id } => write!(
+                f,
+                "same Mii {:08X} made on console {:08X}",
+                mii_id.raw(),
+                system_id.raw()
+            ),
+            Self::CreatorAndLocation {
+                creator_name,
+                country_id,
+                subregion,
+            } => write!(
+                f,
+                "Miis made by \"{creator_name}\" in country {country_id}, subregion {subregion}"
+            ),
+        }
+    }
+}
+
+/// Two ghosts, by index in the slice given to `Identities::resolve`, and why they were linked
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Link {
+    ghosts: (usize, usize),
+    reason: LinkReason,
+}
+
+impl Link {
+    pub fn ghosts(&self) -> (usize, usize) {
+        self.ghosts
+    }
+
+    pub fn reason(&self) -> &LinkReason {
+        &self.reason
+    }
+}
+
+impl Display for Link {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "ghosts {} and {}: {}",
+            self.ghosts.0, self.ghosts.1, self.reason
+        )
+    }
+}
+
+/// Ghosts strongly linked to each other, most likely all driven by one player
+#[derive(Clone, Debug)]
+pub struct Identity {
+    ghost_indices: Vec<usize>,
+    player_ids: Vec<u64>,
+    system_ids: Vec<SystemId>,
+    names: Vec<String>,
+    links: Vec<Link>,
+}
+
+impl Identity {
+    /// Ascending
+    pub fn ghost_indices(&self) -> &[usize] {
+        &self.ghost_indices
+    }
+
+    /// CTGP player IDs of the ghosts, more than one if a Mii was used on several accounts
+    pub fn player_ids(&self) -> &[u64] {
+        &self.player_ids
+    }
+
+    /// Consoles the Miis were made on, in the order they first appear. Ghosts without a Mii
+    /// don't add one.
+    pub fn system_ids(&self) -> &[SystemId] {
+        &self.system_ids
+    }
+
+    /// Every Mii name used, in the order they first appear
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The strong links that joined the ghosts
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+/// Something a person should check before merging accounts
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IdentityConflict {
+    /// One CTGP account drove with Miis made on different consoles. Usually a new Wii, but also
+    /// what a shared or sold account looks like.
+    PlayerIdOnSeveralConsoles {
+        player_id: u64,
+        system_ids: Vec<SystemId>,
+        ghost_indices: Vec<usize>,
+    },
+    /// One Mii was used on several CTGP accounts
+    MiiOnSeveralPlayerIds {
+        system_id: SystemId,
+        mii_id: MiiId,
+        player_ids: Vec<u64>,
+        ghost_indices: Vec<usize>,
+    },
+}
+
+impl IdentityConflict {
+    /// Ghosts involved, ascending
+    pub fn ghost_indices(&self) -> &[usize] {
+        match self {
+            Self::PlayerIdOnSeveralConsoles { ghost_indices, .. }
+            | Self::MiiOnSeveralPlayerIds { ghost_indices, .. } => ghost_indices,
+        }
+    }
+}
+
+impl Display for IdentityConflict {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::PlayerIdOnSeveralConsoles {
+                player_id,
+                system_ids,
+                ..
+            } => {
+                write!(f, "player ID {player_id:016X} used Miis from consoles")?;
+                for system_id in system_ids {
+                    write!(f, " {:08X}", system_id.raw())?;
+                }
+                Ok(())
+            }
+            Self::MiiOnSeveralPlayerIds {
+                system_id,
+                mii_id,
+                player_ids,
+                ..
+            } => {
+                write!(
+                    f,
+                    "Mii {:08X} from console {:08X} used by player IDs",
+                    mii_id.raw(),
+                    system_id.raw()
+                )?;
+                for player_id in player_ids {
+                    write!(f, " {player_id:016X}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Every identity found in a set of ghosts
+#[derive(Clone, Debug)]
+pub struct Identities {
+    identities: Vec<Identity>,
+    /// Index into `identities` for every ghost
+    identity_of: Vec<usize>,
+    suggestions: Vec<Link>,
+    conflicts: Vec<IdentityConflict>,
+}
+
+impl Identities {
+    pub fn resolve(ghosts: &[Ghost]) -> Self {
+        let mut by_player_id: BTreeMap<u64, Vec<usize>> = BTreeMap::new();
+        let mut by_mii: BTreeMap<(u32, u32), Vec<usize>> = BTreeMap::new();
+        let mut by_creator: BTreeMap<(&str, u8, u8), Vec<usize>> = BTreeMap::new();
+
+        for (index, ghost) in ghosts.iter().enumerate() {
+            let header = ghost.header();
+            let mii = header.mii();
+
+            if let Some(metadata) = ghost.ctgp_metadata() {
+                by_player_id
+                    .entry(metadata.player_id())
+                    .or_default()
+                    .push(index);
+            }
+            // Ghosts saved without a Mii have both IDs zeroed
+            if mii.system_id().raw() != 0 || mii.mii_id().raw() != 0 {
+                by_mii
+                    .entry((mii.system_id().raw(), mii.mii_id().raw()))
+                    .or_default()
+                    .push(index);
+            }
+            // 0xFF means the console never had a country set
+            if !mii.creator_name().is_empty() && header.country_id() != 0xFF {
+                by_creator
+                    .entry((mii.creator_name(), header.country_id(), header.subregion()))
+                    .or_default()
+                    .push(index);
+            }
+        }
+
+        let mut links = Vec::new();
+        let mut link_group = |indices: &[usize], reason: LinkReason| {
+            for index in &indices[1..] {
+                links.push(Link {
+                    ghosts: (indices[0], *index),
+                    reason: reason.clone(),
+                });
+            }
+        };
+        for (player_id, indices) in &by_player_id {
+            link_group(indices, LinkReason::PlayerId(*player_id));
+        }
+        for ((system_id, mii_id), indices) in &by_mii {
+            link_group(
+                indices,
+                LinkReason::Mii {
+                    system_id: SystemId::new(*system_id),
+                    mii_id: MiiId::new(*mii_id),
+                },
+            );
+        }
+        for ((creator_name, country_id, subregion), indices) in &by_creator {
+            link_group(
+                indices,
+                LinkReason::CreatorAndLocation {
+                    creator_name: String::from(*creator_name),
+                    country_id: *country_id,
+                    subregion: *subregion,
+                },
+            );
+        }
+
+        let mut parents: Vec<usize> = (0..ghosts.len()).collect();
+        for link in links.iter().filter(|link| link.reason.is_strong()) {
+            let (first, second) = (
+                find(&mut parents, link.ghosts.0),
+                find(&mut parents, link.ghosts.1),
+            );
+            // The smaller index stays the root, so identities come out in ghost order
+            parents[first.max(second)] = first.min(second);
+        }
+
+        let mut identities: Vec<Identity> = Vec::new();
+        let mut identity_of = vec![0; ghosts.len()];
+        for (index, ghost) in ghosts.iter().enumerate() {
+            let root = find(&mut parents, index);
+            let identity_index = if root == index {
+                identities.push(Identity {
+                    ghost_indices: Vec::new(),
+                    player_ids: Vec::new(),
+                    system_ids: Vec::new(),
+                    names: Vec::new(),
+                    links: Vec::new(),
+                });
+                identities.len() - 1
+            } else {
+                identity_of[root]
+            };
+            identity_of[index] = identity_index;
+
+            let identity = &mut identities[identity_index];
+            let mii = ghost.header().mii();
+            identity.ghost_indices.push(index);
+            if let Some(metadata) = ghost.ctgp_metadata() {
+                push_unique(&mut identity.player_ids, metadata.player_id());
+            }
+            push_system_id(&mut identity.system_ids, mii.system_id());
+            push_unique(&mut identity.names, String::from(mii.name()));
+        }
+
+        let mut suggestions: Vec<Link> = Vec::new();
+        for link in links {
+            let (first, second) = (identity_of[link.ghosts.0], identity_of[link.ghosts.1]);
+            if link.reason.is_strong() {
+                identities[first].links.push(link);
+            } else if first != second
+                && !suggestions.iter().any(|suggestion| {
+                    let pair = (
+                        identity_of[suggestion.ghosts.0],
+                        identity_of[suggestion.ghosts.1],
+                    );
+                    pair == (first, second) || pair == (second, first)
+                })
+            {
+                suggestions.push(link);
+            }
+        }
+
+        let mut conflicts = Vec::new();
+        for (player_id, indices) in &by_player_id {
+            let mut system_ids = Vec::new();
+            for index in indices {
+                push_system_id(&mut system_ids, ghosts[*index].header().mii().system_id());
+            }
+            if system_ids.len() > 1 {
+                conflicts.push(IdentityConflict::PlayerIdOnSeveralConsoles {
+                    player_id: *player_id,
+                    system_ids,
+                    ghost_indices: indices.clone(),
+                });
+            }
+        }
+        for ((system_id, mii_id), indices) in &by_mii {
+            let mut player_ids = Vec::new();
+            for index in indices {
+                if let Some(metadata) = ghosts[*index].ctgp_metadata() {
+                    push_unique(&mut player_ids, metadata.player_id());
+                }
+            }
+            if player_ids.len() > 1 {
+                conflicts.push(IdentityConflict::MiiOnSeveralPlayerIds {
+                    system_id: SystemId::new(*system_id),
+                    mii_id: MiiId::new(*mii_id),
+                    player_ids,
+                    ghost_indices: indices.clone(),
+                });
+            }
+        }
+
+        Self {
+            identities,
+            identity_of,
+            suggestions,
+            conflicts,
+        }
+    }
+
+    /// In the order of their first ghost
+    pub fn identities(&self) -> &[Identity] {
+        &self.identities
+    }
+
+    pub fn identity_of(&self, ghost_index: usize) -> Option<&Identity> {
+        self.identities.get(*self.identity_of.get(ghost_index)?)
+    }
+
+    /// Weak links between different identities, at most one per pair of identities
+    pub fn suggestions(&self) -> &[Link] {
+        &self.suggestions
+    }
+
+    pub fn conflicts(&self) -> &[IdentityConflict] {
+        &self.conflicts
+    }
+
+    /// The shortest chain of links from one ghost to the other, `None` if they belong to
+    /// different identities. Empty when both indices are the same ghost.
+    pub fn explain(&self, from: usize, to: usize) -> Option<Vec<&Link>> {
+        let identity = self.identity_of(from)?;
+        if self.identity_of.get(to) != self.identity_of.get(from) {
+            return None;
+        }
+
+        // Breadth first over the identity's links, remembering how each ghost was reached
+        let mut reached_by: BTreeMap<usize, Option<&Link>> = BTreeMap::from([(from, None)]);
+        let mut queue = VecDeque::from([from]);
+        while let Some(ghost) = queue.pop_front() {
+            if ghost == to {
+                break;
+            }
+            for link in &identity.links {
+                let next = match link.ghosts {
+                    (first, second) if first == ghost => second,
+                    (first, second) if second == ghost => first,
+                    _ => continue,
+                };
+                if let Entry::Vacant(entry) = reached_by.entry(next) {
+                    entry.insert(Some(link));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut chain = Vec::new();
+        let mut ghost = to;
+        while let Some(Some(link)) = reached_by.get(&ghost) {
+            chain.push(*link);
+            ghost = if link.ghosts.0 == ghost {
+                link.ghosts.1
+            } else {
+                link.ghosts.0
+            };
+        }
+        chain.reverse();
+
+        Some(chain)
+    }
+}
+
+fn find(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+fn push_unique<T: PartialEq>(values: &mut Vec<T>, value: T) {
+    if !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+/// Like `push_unique`, but skips the zeroed system ID of ghosts saved without a Mii
+fn push_system_id(system_ids: &mut Vec<SystemId>, system_id: SystemId) {
+    if system_id.raw() != 0 {
+        push_unique(system_ids, system_id);
+    }
+}
@@ -26,6 +26,18 @@ pub fn parse_dpad_button(value: u8) -> Result<DPadButton, DPadButtonError> {
     }
 }
 
+impl From<DPadButton> for u8 {
+    fn from(value: DPadButton) -> Self {
+        match value {
+            DPadButton::None => 0,
+            DPadButton::Up => 1,
+            DPadButton::Down => 2,
+            DPadButton::Left => 3,
+            DPadButton::Right => 4,
+        }
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum DPadInputError {
     #[error("Invalid DPad Input")]
@@ -34,7 +46,7 @@ pub enum DPadInputError {
     InvalidButton(#[from] DPadButtonError),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct DPadInput {
     button: DPadButton,
     frame_duration: u32,
@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 #[derive(thiserror::Error, Debug)]
 pub enum FaceButtonError {
     #[error("Non Existent Face Button")]
@@ -12,7 +14,8 @@ pub enum FaceButton {
     Brake,
     Drift,
     Item,
-    Unknown,
+    /// Undefined high bits that were set, in place
+    Unknown(u8),
 }
 
 pub fn parse_face_buttons(value: u8) -> Result<Vec<FaceButton>, FaceButtonError> {
@@ -33,9 +36,9 @@ pub fn parse_face_buttons(value: u8) -> Result<Vec<FaceButton>, FaceButtonError>
     if value & 0x04 != 0 {
         buttons.push(FaceButton::Item);
     }
-    // 0x40 is the CTGP pause mask and would trigger this otherwise
-    if value & 0xF0 != 0 && value & 0x40 == 0 {
-        buttons.push(FaceButton::Unknown);
+    // 0x40 is the CTGP pause mask, see `InputData::raw_input_data`. Every other undefined bit is kept
+    if value & 0xB0 != 0 {
+        buttons.push(FaceButton::Unknown(value & 0xB0));
     }
 
     if value & 0x08 != 0 && value & 0x02 == 0 {
@@ -43,13 +46,28 @@ pub fn parse_face_buttons(value: u8) -> Result<Vec<FaceButton>, FaceButtonError>
         return Err(FaceButtonError::IllegalDriftInput);
     }
 
-    if value != 0x00 && value & 0x40 == 0 && buttons.is_empty() {
+    if value & !0x40 != 0x00 && buttons.is_empty() {
         return Err(FaceButtonError::NonExistentFaceButton);
     }
 
     Ok(buttons)
 }
 
+/// Inverse of `parse_face_buttons` except for the CTGP pause mask, drifting sets both the brake
+/// and the drift flag
+pub fn face_buttons_to_byte(buttons: &[FaceButton]) -> u8 {
+    buttons.iter().fold(0, |value, button| {
+        value
+            | match button {
+                FaceButton::Accelerator => 0x01,
+                FaceButton::Brake => 0x02,
+                FaceButton::Item => 0x04,
+                FaceButton::Drift => 0x0A,
+                FaceButton::Unknown(bits) => *bits,
+            }
+    })
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum FaceInputError {
     #[error("Invalid Face Input")]
@@ -58,7 +76,7 @@ pub enum FaceInputError {
     InvalidButton(#[from] FaceButtonError),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct FaceInput {
     buttons: Vec<FaceButton>,
     frame_duration: u32,
@@ -1,5 +1,6 @@
 use crate::input_data::dpad_input::DPadButton;
 use crate::input_data::face_input::FaceButton;
+use alloc::vec::Vec;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Input {
@@ -0,0 +1,53 @@
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use crate::input_data::input::Input;
+
+/// Part of the race a `LapInputs` covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LapSegment {
+    /// The 3-2-1-GO countdown before the race timer starts
+    Countdown,
+    /// Lap index, starting at 0
+    Lap(u8),
+}
+
+/// Inputs of a single lap (or the countdown). Inputs held across a lap line are split
+/// so each segment only contains the frames spent in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LapInputs {
+    segment: LapSegment,
+    frames: RangeInclusive<u32>,
+    inputs: Vec<Input>,
+}
+
+impl LapInputs {
+    pub(crate) fn new(
+        segment: LapSegment,
+        frames: RangeInclusive<u32>,
+        inputs: Vec<Input>,
+    ) -> Self {
+        Self {
+            segment,
+            frames,
+            inputs,
+        }
+    }
+
+    pub fn segment(&self) -> LapSegment {
+        self.segment
+    }
+
+    /// Frames covered, counted from 1 at the first frame of input like `RaceClock`
+    pub fn frames(&self) -> &RangeInclusive<u32> {
+        &self.frames
+    }
+
+    pub fn frame_count(&self) -> u32 {
+        (self.frames.end() + 1).saturating_sub(*self.frames.start())
+    }
+
+    pub fn inputs(&self) -> &[Input] {
+        &self.inputs
+    }
+}
@@ -1,11 +1,21 @@
 use crate::input_data::dpad_input::{DPadButton, DPadInput};
-use crate::input_data::face_input::FaceInput;
+use crate::input_data::face_input::{FaceInput, face_buttons_to_byte};
 use crate::input_data::input::Input;
+use crate::input_data::lap_inputs::{LapInputs, LapSegment};
 use crate::input_data::stick_input::StickInput;
+use crate::{
+    ctgp_metadata::exact_finish_time::ExactFinishTime, header::Header,
+    timing::race_clock::RaceClock,
+};
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
 
 pub mod dpad_input;
 pub mod face_input;
 pub mod input;
+pub mod lap_inputs;
 pub mod stick_input;
 
 #[derive(thiserror::Error, Debug)]
@@ -16,10 +26,18 @@ pub enum InputDataError {
     DPadInputError(#[from] dpad_input::DPadInputError),
     #[error("Stick Input Error: {0}")]
     StickInputError(#[from] stick_input::StickInputError),
+    #[error("Input data is shorter than its input counts say")]
+    TooShort,
+    #[error("Compressed input data isn't valid Yaz1")]
+    InvalidYaz1,
+    #[cfg(feature = "std")]
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
 }
 
 /// Handles all input data being read
 /// Tockdom wiki: https://wiki.tockdom.com/wiki/RKG_(File_Format)#Controller_Input_Data
+#[derive(Clone)]
 pub struct InputData {
     face_input_count: u16,
     stick_input_count: u16,
@@ -27,52 +45,36 @@ pub struct InputData {
     face_inputs: Vec<FaceInput>,
     stick_inputs: Vec<StickInput>,
     dpad_inputs: Vec<DPadInput>,
+    raw_input_data: Vec<u8>,
 }
 
 impl InputData {
     pub fn new(input_data: &[u8]) -> Result<Self, InputDataError> {
-        let input_data = if input_data[4..8] == [0x59, 0x61, 0x7A, 0x31] {
+        let input_data = if input_data.get(4..8) == Some(b"Yaz1") {
             // YAZ1 header, decompress
-            yaz1_decompress(&input_data[4..]).unwrap()
+            yaz1_decompress(&input_data[4..]).ok_or(InputDataError::InvalidYaz1)?
         } else {
             Vec::from(input_data)
         };
 
-        let face_input_count = u16::from_be_bytes([input_data[0], input_data[1]]);
-        let stick_input_count = u16::from_be_bytes([input_data[2], input_data[3]]);
-        let dpad_input_count = u16::from_be_bytes([input_data[4], input_data[5]]);
+        let counts = input_data.get(..8).ok_or(InputDataError::TooShort)?;
+        let face_input_count = u16::from_be_bytes([counts[0], counts[1]]);
+        let stick_input_count = u16::from_be_bytes([counts[2], counts[3]]);
+        let dpad_input_count = u16::from_be_bytes([counts[4], counts[5]]);
         // bytes 6-7: padding
 
-        let mut current_byte = 8;
-        let mut face_inputs: Vec<FaceInput> = Vec::with_capacity(face_input_count as usize);
-        while current_byte < 8 + face_input_count * 2 {
-            let idx = current_byte as usize;
-            let input = &input_data[idx..idx + 2];
-            face_inputs.push(FaceInput::try_from(input)?);
-            current_byte += 2;
-        }
+        // Counts are u16, so none of this can overflow a usize
+        let face_end = 8 + face_input_count as usize * 2;
+        let stick_end = face_end + stick_input_count as usize * 2;
+        let dpad_end = stick_end + dpad_input_count as usize * 2;
 
-        current_byte = 8 + face_input_count * 2;
-        let mut stick_inputs: Vec<StickInput> = Vec::with_capacity(stick_input_count as usize);
-        while current_byte < 8 + (face_input_count + stick_input_count) * 2 {
-            let idx = current_byte as usize;
-            let input = &input_data[idx..idx + 2];
-            stick_inputs.push(StickInput::try_from(input)?);
-            current_byte += 2;
-        }
-
-        current_byte = 8 + (face_input_count + stick_input_count) * 2;
-        let mut dpad_inputs: Vec<DPadInput> = Vec::with_capacity(dpad_input_count as usize);
-        while current_byte < 8 + (face_input_count + stick_input_count + dpad_input_count) * 2 {
-            let idx = current_byte as usize;
-            let input = &input_data[idx..idx + 2];
-            dpad_inputs.push(DPadInput::try_from(input)?);
-            current_byte += 2;
-        }
+        let mut face_inputs = read_inputs::<FaceInput>(&input_data, 8, face_end)?;
+        let mut stick_inputs = read_inputs::<StickInput>(&input_data, face_end, stick_end)?;
+        let dpad_inputs = read_inputs::<DPadInput>(&input_data, stick_end, dpad_end)?;
 
         // Combine adjacent inputs when the same button is held across multiple bytes
         // (each input byte has a 255-frame limit, so buttons held longer need additional bytes)
-        for index in (0..face_inputs.len() - 1).rev() {
+        for index in (0..face_inputs.len().saturating_sub(1)).rev() {
             if face_inputs[index] == face_inputs[index + 1] {
                 let f1 = face_inputs[index].frame_duration();
                 let f2 = face_inputs[index + 1].frame_duration();
@@ -81,7 +83,7 @@ impl InputData {
             }
         }
 
-        for index in (0..stick_inputs.len() - 1).rev() {
+        for index in (0..stick_inputs.len().saturating_sub(1)).rev() {
             if stick_inputs[index] == stick_inputs[index + 1] {
                 let f1 = stick_inputs[index].frame_duration();
                 let f2 = stick_inputs[index + 1].frame_duration();
@@ -97,9 +99,32 @@ impl InputData {
             face_inputs,
             stick_inputs,
             dpad_inputs,
+            raw_input_data: input_data,
         })
     }
 
+    /// Reads the input data that follows `header` and nothing more: the Yaz1 block and its
+    /// length if the header says it's compressed, `decompressed_input_data_length` bytes otherwise.
+    /// Leaves the reader at the CRC32.
+    #[cfg(feature = "std")]
+    pub fn read_from<R: Read>(reader: R, header: &Header) -> Result<Self, InputDataError> {
+        Self::new(&read_input_section(reader, header)?)
+    }
+
+    /// Writes the input data in the format `header` declares, compressing it if needed. The
+    /// compressed bytes may differ from the original file, Yaz1 has several valid encodings.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: Write>(&self, mut writer: W, header: &Header) -> Result<(), InputDataError> {
+        if header.is_compressed() {
+            let compressed = yaz1_compress(&self.raw_input_data);
+            writer.write_all(&(compressed.len() as u32).to_be_bytes())?;
+            writer.write_all(&compressed)?;
+        } else {
+            writer.write_all(&self.raw_input_data)?;
+        }
+        Ok(())
+    }
+
     pub fn inputs(&self) -> Vec<Input> {
         let mut result = Vec::new();
 
@@ -128,7 +153,7 @@ impl InputData {
                 .map(|f| f.frame_duration() - face_offset)
                 .unwrap_or(u32::MAX);
             let stick_remaining = stick
-                .map(|s| s.frame_duration() as u32 - stick_offset)
+                .map(|s| s.frame_duration() - stick_offset)
                 .unwrap_or(u32::MAX);
             let dpad_remaining = dpad
                 .map(|d| d.frame_duration() - dpad_offset)
@@ -163,7 +188,7 @@ impl InputData {
                 face_idx += 1;
                 face_offset = 0;
             }
-            if stick.is_some() && stick_offset >= stick.unwrap().frame_duration() as u32 {
+            if stick.is_some() && stick_offset >= stick.unwrap().frame_duration() {
                 stick_idx += 1;
                 stick_offset = 0;
             }
@@ -176,6 +201,99 @@ impl InputData {
         result
     }
 
+    /// Splits the inputs into the countdown and one segment per lap, using the header's lap split
+    /// times converted to frames. The last lap always runs until the final frame of input.
+    pub fn laps(&self, header: &Header) -> Vec<LapInputs> {
+        let race_clock = RaceClock::default();
+        let frame_count = self.frame_count();
+
+        let mut segments = vec![(LapSegment::Countdown, race_clock.countdown_frames())];
+        let lap_boundaries = race_clock.lap_boundaries(header);
+        for (lap, boundary) in lap_boundaries.iter().enumerate() {
+            let end = if lap + 1 == lap_boundaries.len() {
+                frame_count
+            } else {
+                *boundary
+            };
+            segments.push((LapSegment::Lap(lap as u8), end));
+        }
+
+        let mut inputs = self.inputs().into_iter();
+        let mut current = inputs.next();
+        let mut consumed = 0u32;
+        let mut start = 1u32;
+        let mut laps = Vec::with_capacity(segments.len());
+
+        for (segment, end) in segments {
+            let end = end.clamp(start.saturating_sub(1), frame_count);
+            let mut lap_inputs = Vec::new();
+            let mut remaining = (end + 1).saturating_sub(start);
+
+            while remaining > 0 {
+                let Some(input) = &current else {
+                    break;
+                };
+                let duration = (input.frame_duration() - consumed).min(remaining);
+                lap_inputs.push(Input::new(
+                    input.face_buttons().to_vec(),
+                    input.stick_x(),
+                    input.stick_y(),
+                    input.dpad_button(),
+                    duration,
+                ));
+
+                remaining -= duration;
+                consumed += duration;
+                if consumed == input.frame_duration() {
+                    current = inputs.next();
+                    consumed = 0;
+                }
+            }
+
+            laps.push(LapInputs::new(segment, start..=end, lap_inputs));
+            start = end + 1;
+        }
+
+        laps
+    }
+
+    /// Length of the inputs in frames, including the countdown
+    pub fn frame_count(&self) -> u32 {
+        self.face_inputs.iter().map(|f| f.frame_duration()).sum()
+    }
+
+    /// One 4-byte record per frame: the face button bits, stick X, stick Y and the D-pad button
+    /// (0 for none). This is the layout of `InputData.FRAME_DTYPE` in the Python bindings.
+    pub fn frame_records(&self) -> Vec<u8> {
+        let mut records = Vec::with_capacity(self.frame_count() as usize * 4);
+
+        for input in self.inputs() {
+            let record = [
+                face_buttons_to_byte(input.face_buttons()),
+                input.stick_x() as u8,
+                input.stick_y() as u8,
+                input.dpad_button().into(),
+            ];
+
+            for _ in 0..input.frame_duration() {
+                records.extend_from_slice(&record);
+            }
+        }
+
+        records
+    }
+
+    /// Race timer on the last frame of input, which is when the ghost crosses the finish line
+    pub fn end_time(&self, race_clock: RaceClock) -> Option<ExactFinishTime> {
+        race_clock.frame_to_time(self.frame_count())
+    }
+
+    /// Input data as read (after Yaz1 decompression), before inputs held across several
+    /// entries are merged. Keeps bits the parsed inputs drop, like the CTGP pause mask
+    pub fn raw_input_data(&self) -> &[u8] {
+        &self.raw_input_data
+    }
+
     pub fn face_inputs(&self) -> &[FaceInput] {
         &self.face_inputs
     }
@@ -201,6 +319,58 @@ impl InputData {
     }
 }
 
+/// Reads the input data section as stored in the file, see `InputData::read_from`
+#[cfg(feature = "std")]
+pub(crate) fn read_input_section<R: Read>(
+    mut reader: R,
+    header: &Header,
+) -> std::io::Result<Vec<u8>> {
+    let decompressed_length = header.decompressed_input_data_length() as usize;
+    if !header.is_compressed() {
+        let mut section = vec![0u8; decompressed_length];
+        reader.read_exact(&mut section)?;
+        return Ok(section);
+    }
+
+    let mut length = [0u8; 0x04];
+    reader.read_exact(&mut length)?;
+    let length = u32::from_be_bytes(length) as usize;
+    // Yaz1 never grows data by more than a flag byte per 8 bytes, this leaves room for padding
+    // while keeping a broken length from allocating gigabytes
+    if length > 0x20 + decompressed_length * 2 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Yaz1 block is longer than the input data it decompresses to",
+        ));
+    }
+
+    let mut section = vec![0u8; 0x04 + length];
+    section[..0x04].copy_from_slice(&(length as u32).to_be_bytes());
+    reader.read_exact(&mut section[0x04..])?;
+    Ok(section)
+}
+
+/// Reads the 2-byte inputs in `start..end`, in order, so an invalid input is reported even if
+/// the data ends before `end`
+fn read_inputs<'a, T>(
+    input_data: &'a [u8],
+    start: usize,
+    end: usize,
+) -> Result<Vec<T>, InputDataError>
+where
+    T: TryFrom<&'a [u8]>,
+    InputDataError: From<T::Error>,
+{
+    let mut inputs = Vec::with_capacity((end - start) / 2);
+    for offset in (start..end).step_by(2) {
+        let input = input_data
+            .get(offset..offset + 2)
+            .ok_or(InputDataError::TooShort)?;
+        inputs.push(T::try_from(input)?);
+    }
+    Ok(inputs)
+}
+
 /// Decompress YAZ1-compressed input data
 /// Adapted from https://github.com/AtishaRibeiro/InputDisplay/blob/master/InputDisplay/Core/Yaz1dec.cs
 pub fn yaz1_decompress(data: &[u8]) -> Option<Vec<u8>> {
@@ -210,6 +380,10 @@ pub fn yaz1_decompress(data: &[u8]) -> Option<Vec<u8>> {
     }
 
     let uncompressed_size = u32::from_be_bytes([data[4], data[5], data[6], data[7]]) as usize;
+    // A chunk is at most 3 bytes and copies at most 0x111, don't allocate for sizes that can't be
+    if uncompressed_size > (data.len() - 16).saturating_mul(0x111) {
+        return None;
+    }
 
     let mut result = Vec::with_capacity(uncompressed_size);
 
@@ -230,6 +404,71 @@ pub fn yaz1_decompress(data: &[u8]) -> Option<Vec<u8>> {
     }
 }
 
+/// Compress data to YAZ1, the inverse of `yaz1_decompress`
+/// Uses a greedy search over the full 0x1000 byte window, which is plenty fast for ghost-sized data
+pub fn yaz1_compress(data: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(16 + data.len() + data.len() / 8 + 1);
+    result.extend_from_slice(b"Yaz1");
+    result.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    result.extend_from_slice(&[0; 8]);
+
+    let mut src_pos = 0;
+    while src_pos < data.len() {
+        // Every group of 8 chunks is preceded by a "code" byte, 1 = straight copy, 0 = RLE
+        let code_byte_pos = result.len();
+        result.push(0);
+
+        for bit in (0..8).rev() {
+            if src_pos >= data.len() {
+                break;
+            }
+
+            let (dist, num_bytes) = find_longest_match(data, src_pos);
+            if num_bytes < 3 {
+                result[code_byte_pos] |= 1 << bit;
+                result.push(data[src_pos]);
+                src_pos += 1;
+                continue;
+            }
+
+            if num_bytes < 0x12 {
+                result.push((((num_bytes - 2) << 4) | (dist >> 8)) as u8);
+                result.push(dist as u8);
+            } else {
+                result.push((dist >> 8) as u8);
+                result.push(dist as u8);
+                result.push((num_bytes - 0x12) as u8);
+            }
+            src_pos += num_bytes;
+        }
+    }
+
+    result
+}
+
+/// Returns (distance - 1, length) of the longest earlier occurence of the data at `pos`
+fn find_longest_match(data: &[u8], pos: usize) -> (usize, usize) {
+    let max_num_bytes = (data.len() - pos).min(0x111);
+    let mut best = (0, 0);
+
+    for copy_source in pos.saturating_sub(0x1000)..pos {
+        let mut num_bytes = 0;
+        // Runs may overlap the current position, like in the decompressor
+        while num_bytes < max_num_bytes && data[copy_source + num_bytes] == data[pos + num_bytes] {
+            num_bytes += 1;
+        }
+
+        if num_bytes > best.1 {
+            best = (pos - copy_source - 1, num_bytes);
+            if num_bytes == max_num_bytes {
+                break;
+            }
+        }
+    }
+
+    best
+}
+
 fn decompress_block(src: &[u8], offset: usize, uncompressed_size: usize) -> Option<Vec<u8>> {
     let mut dst = Vec::with_capacity(uncompressed_size);
     let mut src_pos = offset;
@@ -267,7 +506,7 @@ fn decompress_block(src: &[u8], offset: usize, uncompressed_size: usize) -> Opti
             src_pos += 1;
 
             let dist = (((byte1 & 0xF) as usize) << 8) | (byte2 as usize);
-            let copy_source = dst.len().wrapping_sub(dist + 1);
+            let copy_source = dst.len().checked_sub(dist + 1)?;
 
             let mut num_bytes = (byte1 >> 4) as usize;
             if num_bytes == 0 {
@@ -282,9 +521,6 @@ fn decompress_block(src: &[u8], offset: usize, uncompressed_size: usize) -> Opti
 
             // Copy run - must handle overlapping copies
             for i in 0..num_bytes {
-                if copy_source + i >= dst.len() {
-                    return None;
-                }
                 let byte = dst[copy_source + i];
                 dst.push(byte);
             }
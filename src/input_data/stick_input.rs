@@ -4,7 +4,7 @@ pub enum StickInputError {
     InvalidStickInput,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct StickInput {
     x: i8,
     y: i8,
@@ -0,0 +1,330 @@
+use alloc::vec::Vec;
+use core::fmt::Display;
+
+use crate::{
+    crc::{crc16, crc32},
+    input_data::yaz1_decompress,
+};
+
+/// Size of the RKG header, the Mii CRC16 is its last 2 bytes
+const HEADER_SIZE: usize = 0x88;
+const MII_OFFSET: usize = 0x3C;
+const MII_CRC16_OFFSET: usize = 0x86;
+const INPUT_DATA_LENGTH_OFFSET: usize = 0x0E;
+/// Compressed ghosts store the length of the Yaz1 block before it
+const YAZ1_LENGTH_OFFSET: usize = 0x88;
+const YAZ1_OFFSET: usize = 0x8C;
+
+/// A single problem with the structure of a ghost file. Every offset is from the start of the file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IntegrityIssue {
+    /// File can't even hold a header and a CRC32
+    FileTooShort {
+        length: usize,
+    },
+    NotRKGD,
+    Crc32Mismatch {
+        offset: usize,
+        stored: u32,
+        computed: u32,
+    },
+    /// CRC32 of the whole file at the end of a CTGP footer
+    FooterCrc32Mismatch {
+        offset: usize,
+        stored: u32,
+        computed: u32,
+    },
+    MiiCrc16Mismatch {
+        offset: usize,
+        stored: u16,
+        computed: u16,
+    },
+    /// `decompressed_input_data_length` disagrees with the size the Yaz1 block decompresses to
+    InputDataLengthMismatch {
+        offset: usize,
+        declared: usize,
+        actual: usize,
+    },
+    /// The input counts need more bytes than the input block has
+    InputCountsExceedInputData {
+        offset: usize,
+        required: usize,
+        length: usize,
+    },
+    /// Compressed flag is set, but there's no Yaz1 block
+    MissingYaz1 {
+        offset: usize,
+    },
+    /// The Yaz1 stream doesn't produce the size in its own header
+    Yaz1DecompressionFailed {
+        offset: usize,
+    },
+    /// File ends before the RKG data does, e.g. the Yaz1 block length at 0x88 is too large
+    Truncated {
+        expected_length: usize,
+        length: usize,
+    },
+    /// CKGD `metadata_size` doesn't match the space between the RKG data and the end of the file
+    MetadataSizeMismatch {
+        offset: usize,
+        metadata_size: usize,
+        expected: usize,
+    },
+    /// Bytes after the end of the RKG data that no part of the file accounts for
+    TrailingData {
+        offset: usize,
+        length: usize,
+    },
+}
+
+impl Display for IntegrityIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::FileTooShort { length } => write!(f, "File is only {length:#X} bytes long"),
+            Self::NotRKGD => write!(f, "File doesn't start with RKGD"),
+            Self::Crc32Mismatch {
+                offset,
+                stored,
+                computed,
+            } => write!(
+                f,
+                "CRC32 at {offset:#X} is {stored:08X}, data hashes to {computed:08X}"
+            ),
+            Self::FooterCrc32Mismatch {
+                offset,
+                stored,
+                computed,
+            } => write!(
+                f,
+                "CTGP footer CRC32 at {offset:#X} is {stored:08X}, file hashes to {computed:08X}"
+            ),
+            Self::MiiCrc16Mismatch {
+                offset,
+                stored,
+                computed,
+            } => write!(
+                f,
+                "Mii CRC16 at {offset:#X} is {stored:04X}, Mii hashes to {computed:04X}"
+            ),
+            Self::InputDataLengthMismatch {
+                offset,
+                declared,
+                actual,
+            } => write!(
+                f,
+                "Input data length at {offset:#X} is {declared:#X}, input data is {actual:#X} bytes"
+            ),
+            Self::InputCountsExceedInputData {
+                offset,
+                required,
+                length,
+            } => write!(
+                f,
+                "Input counts at {offset:#X} need {required:#X} bytes, input data is {length:#X} bytes"
+            ),
+            Self::MissingYaz1 { offset } => {
+                write!(
+                    f,
+                    "Ghost is marked compressed but has no Yaz1 at {offset:#X}"
+                )
+            }
+            Self::Yaz1DecompressionFailed { offset } => {
+                write!(f, "Yaz1 block at {offset:#X} doesn't decompress")
+            }
+            Self::Truncated {
+                expected_length,
+                length,
+            } => write!(
+                f,
+                "File is {length:#X} bytes, RKG data needs {expected_length:#X}"
+            ),
+            Self::MetadataSizeMismatch {
+                offset,
+                metadata_size,
+                expected,
+            } => write!(
+                f,
+                "CTGP metadata size at {offset:#X} is {metadata_size:#X}, footer is {expected:#X} bytes"
+            ),
+            Self::TrailingData { offset, length } => {
+                write!(f, "{length:#X} unexpected bytes at {offset:#X}")
+            }
+        }
+    }
+}
+
+/// Every structural problem found in a ghost file
+#[derive(Clone, Debug, Default)]
+pub struct IntegrityReport {
+    issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    pub fn is_intact(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn issues(&self) -> &[IntegrityIssue] {
+        &self.issues
+    }
+}
+
+/// Checks a whole ghost file, including any CTGP footer, without parsing its contents.
+/// Works on files `Ghost::new` rejects, so broken ghosts can still be diagnosed.
+pub fn verify_integrity(data: &[u8]) -> IntegrityReport {
+    let mut issues = Vec::new();
+
+    if data.len() < HEADER_SIZE + 0x04 {
+        issues.push(IntegrityIssue::FileTooShort { length: data.len() });
+        return IntegrityReport { issues };
+    }
+    if data[0..4] != *b"RKGD" {
+        issues.push(IntegrityIssue::NotRKGD);
+    }
+
+    let stored = u16::from_be_bytes([data[MII_CRC16_OFFSET], data[MII_CRC16_OFFSET + 1]]);
+    let computed = crc16(&data[MII_OFFSET..MII_CRC16_OFFSET]);
+    if stored != computed {
+        issues.push(IntegrityIssue::MiiCrc16Mismatch {
+            offset: MII_CRC16_OFFSET,
+            stored,
+            computed,
+        });
+    }
+
+    let declared_input_length = u16::from_be_bytes([
+        data[INPUT_DATA_LENGTH_OFFSET],
+        data[INPUT_DATA_LENGTH_OFFSET + 1],
+    ]) as usize;
+    let is_compressed = data[0x0C] & 0x08 != 0;
+
+    let rkg_end = if is_compressed {
+        let yaz1_length =
+            u32::from_be_bytes(data[YAZ1_LENGTH_OFFSET..YAZ1_OFFSET].try_into().unwrap()) as usize;
+        let yaz1_end = YAZ1_OFFSET.saturating_add(yaz1_length);
+        match data.get(YAZ1_OFFSET..yaz1_end) {
+            Some(yaz1) => verify_yaz1(yaz1, declared_input_length, &mut issues),
+            None if data.get(YAZ1_OFFSET..YAZ1_OFFSET + 4) != Some(b"Yaz1") => {
+                issues.push(IntegrityIssue::MissingYaz1 {
+                    offset: YAZ1_OFFSET,
+                });
+            }
+            // The block runs past the end of the file, reported as truncation below
+            None => (),
+        }
+        yaz1_end.saturating_add(0x04)
+    } else {
+        if let Some(input_data) = data.get(HEADER_SIZE..HEADER_SIZE + declared_input_length) {
+            verify_input_counts(input_data, HEADER_SIZE, &mut issues);
+        }
+        HEADER_SIZE + declared_input_length + 0x04
+    };
+
+    let is_ctgp = data[data.len() - 0x08..data.len() - 0x04] == *b"CKGD";
+    let data_end = if is_ctgp {
+        let offset = data.len() - 0x0C;
+        let metadata_size =
+            u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap()) as usize;
+        let expected = (data.len() - 0x04).saturating_sub(rkg_end);
+        if metadata_size != expected {
+            issues.push(IntegrityIssue::MetadataSizeMismatch {
+                offset,
+                metadata_size,
+                expected,
+            });
+        }
+
+        let offset = data.len() - 0x04;
+        let stored = u32::from_be_bytes(data[offset..].try_into().unwrap());
+        let computed = crc32(&data[..offset]);
+        if stored != computed {
+            issues.push(IntegrityIssue::FooterCrc32Mismatch {
+                offset,
+                stored,
+                computed,
+            });
+        }
+
+        // A broken metadata size is reported above, the RKG data may still be intact
+        offset
+    } else {
+        data.len()
+    };
+
+    if rkg_end > data_end {
+        issues.push(IntegrityIssue::Truncated {
+            expected_length: rkg_end,
+            length: data_end,
+        });
+    } else {
+        let offset = rkg_end - 0x04;
+        let stored = u32::from_be_bytes(data[offset..rkg_end].try_into().unwrap());
+        let computed = crc32(&data[..offset]);
+        if stored != computed {
+            issues.push(IntegrityIssue::Crc32Mismatch {
+                offset,
+                stored,
+                computed,
+            });
+        }
+
+        if !is_ctgp && data.len() > rkg_end {
+            issues.push(IntegrityIssue::TrailingData {
+                offset: rkg_end,
+                length: data.len() - rkg_end,
+            });
+        }
+    }
+
+    IntegrityReport { issues }
+}
+
+fn verify_yaz1(yaz1: &[u8], declared_input_length: usize, issues: &mut Vec<IntegrityIssue>) {
+    if yaz1.len() < 0x10 || yaz1[0..4] != *b"Yaz1" {
+        issues.push(IntegrityIssue::MissingYaz1 {
+            offset: YAZ1_OFFSET,
+        });
+        return;
+    }
+
+    match yaz1_decompress(yaz1) {
+        Some(input_data) => {
+            if input_data.len() != declared_input_length {
+                issues.push(IntegrityIssue::InputDataLengthMismatch {
+                    offset: INPUT_DATA_LENGTH_OFFSET,
+                    declared: declared_input_length,
+                    actual: input_data.len(),
+                });
+            }
+            // Offsets inside decompressed data don't exist in the file, point at the Yaz1 block
+            verify_input_counts(&input_data, YAZ1_OFFSET, issues);
+        }
+        None => issues.push(IntegrityIssue::Yaz1DecompressionFailed {
+            offset: YAZ1_OFFSET,
+        }),
+    }
+}
+
+fn verify_input_counts(input_data: &[u8], offset: usize, issues: &mut Vec<IntegrityIssue>) {
+    let Some(counts) = input_data.get(0..6) else {
+        issues.push(IntegrityIssue::InputCountsExceedInputData {
+            offset,
+            required: 0x08,
+            length: input_data.len(),
+        });
+        return;
+    };
+
+    let required = 0x08
+        + counts
+            .chunks_exact(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]) as usize * 2)
+            .sum::<usize>();
+    if required > input_data.len() {
+        issues.push(IntegrityIssue::InputCountsExceedInputData {
+            offset,
+            required,
+            length: input_data.len(),
+        });
+    }
+}
@@ -0,0 +1,162 @@
+#[derive(thiserror::Error, Debug)]
+pub enum LayoutError {
+    #[error("{value:#X} doesn't fit in the {width} bits of {field}")]
+    ValueTooLarge {
+        field: &'static str,
+        width: u8,
+        value: u32,
+    },
+    #[error("{field} ends at {end:#X}, data is only {length:#X} bytes")]
+    OutOfBounds {
+        field: &'static str,
+        end: usize,
+        length: usize,
+    },
+}
+
+/// A packed big-endian field: `width` bits, starting `bit` bits after the most significant bit
+/// of the byte at `offset`. Describes where a value lives, so the same description reads and writes it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BitField {
+    name: &'static str,
+    offset: usize,
+    bit: u8,
+    width: u8,
+}
+
+impl BitField {
+    pub const fn new(name: &'static str, offset: usize, bit: u8, width: u8) -> Self {
+        assert!(bit < 8, "bit must point inside the first byte");
+        assert!(width > 0 && width <= 32, "width must be 1..=32 bits");
+
+        Self {
+            name,
+            offset,
+            bit,
+            width,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        self.name
+    }
+
+    /// Byte the field starts in
+    pub const fn offset(self) -> usize {
+        self.offset
+    }
+
+    /// Bits to skip in the first byte, counted from the most significant bit
+    pub const fn bit(self) -> u8 {
+        self.bit
+    }
+
+    pub const fn width(self) -> u8 {
+        self.width
+    }
+
+    /// Amount of bytes the field touches
+    pub const fn byte_len(self) -> usize {
+        (self.bit as usize + self.width as usize).div_ceil(8)
+    }
+
+    pub const fn max_value(self) -> u32 {
+        u32::MAX >> (32 - self.width as u32)
+    }
+
+    /// The same field `bytes` further into the data, for repeated fields like lap splits
+    pub const fn offset_by(self, bytes: usize) -> Self {
+        Self {
+            offset: self.offset + bytes,
+            ..self
+        }
+    }
+
+    /// The same field with its offset counted from `start` instead, to use it on a slice of the data
+    pub const fn relative_to(self, start: usize) -> Self {
+        assert!(self.offset >= start, "field starts before `start`");
+
+        Self {
+            offset: self.offset - start,
+            ..self
+        }
+    }
+
+    pub fn read(self, data: &[u8]) -> Result<u32, LayoutError> {
+        let (chunk, shift) = self.load(data)?;
+        Ok(((chunk >> shift) & self.max_value() as u64) as u32)
+    }
+
+    pub fn read_bool(self, data: &[u8]) -> Result<bool, LayoutError> {
+        self.read(data).map(|value| value != 0)
+    }
+
+    /// Writes `value` into the field, leaving every bit around it untouched
+    pub fn write(self, data: &mut [u8], value: u32) -> Result<(), LayoutError> {
+        if value > self.max_value() {
+            return Err(LayoutError::ValueTooLarge {
+                field: self.name,
+                width: self.width,
+                value,
+            });
+        }
+
+        let (chunk, shift) = self.load(data)?;
+        let mask = (self.max_value() as u64) << shift;
+        let chunk = (chunk & !mask) | ((value as u64) << shift);
+
+        let len = self.byte_len();
+        let bytes = chunk.to_be_bytes();
+        data[self.offset..self.offset + len].copy_from_slice(&bytes[8 - len..]);
+        Ok(())
+    }
+
+    pub fn write_bool(self, data: &mut [u8], value: bool) -> Result<(), LayoutError> {
+        self.write(data, value as u32)
+    }
+
+    /// Returns the bytes the field touches as one big-endian number, and how far the field is
+    /// from its least significant bit
+    fn load(self, data: &[u8]) -> Result<(u64, u32), LayoutError> {
+        let len = self.byte_len();
+        let bytes = data
+            .get(self.offset..self.offset + len)
+            .ok_or(LayoutError::OutOfBounds {
+                field: self.name,
+                end: self.offset + len,
+                length: data.len(),
+            })?;
+
+        let chunk = bytes
+            .iter()
+            .fold(0u64, |chunk, byte| (chunk << 8) | *byte as u64);
+        let shift = (len * 8) as u32 - self.bit as u32 - self.width as u32;
+        Ok((chunk, shift))
+    }
+}
+
+/// Declares a `BitField` constant per field and a table listing all of them in order, e.g.
+/// `bit_layout! { FIELDS { LAP_COUNT: 0x10, 0, 8; } }`, where the numbers are the byte offset,
+/// the bits to skip in that byte and the width in bits.
+macro_rules! bit_layout {
+    (
+        $(#[$table_meta:meta])*
+        $table:ident {
+            $(
+                $(#[$field_meta:meta])*
+                $name:ident: $offset:expr, $bit:expr, $width:expr;
+            )*
+        }
+    ) => {
+        $(
+            $(#[$field_meta])*
+            pub const $name: $crate::layout::BitField =
+                $crate::layout::BitField::new(stringify!($name), $offset, $bit, $width);
+        )*
+
+        $(#[$table_meta])*
+        pub const $table: &[$crate::layout::BitField] = &[$($name),*];
+    };
+}
+
+pub(crate) use bit_layout;
@@ -0,0 +1,212 @@
+//! Text exports of leaderboards. Tracks and categories use their Rust names, track SHA1s are hex.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write;
+
+use super::{Leaderboard, LeaderboardEntry};
+
+const CSV_COLUMNS: [&str; 13] = [
+    "board",
+    "track",
+    "track_sha1",
+    "category",
+    "rank",
+    "player",
+    "name",
+    "time",
+    "exact_time",
+    "lap",
+    "character",
+    "vehicle",
+    "date",
+];
+
+/// A JSON array with one object per board, each with its `entries` fastest first
+pub fn to_json(boards: &[Leaderboard]) -> String {
+    let boards: Vec<String> = boards
+        .iter()
+        .map(|board| {
+            let entries: Vec<String> = board
+                .entries()
+                .iter()
+                .map(|entry| {
+                    format!(
+                        "{{\"rank\":{},\"player\":{},\"name\":{},\"time\":{},\"exact_time\":{},\
+                        \"lap\":{},\"character\":{},\"vehicle\":{},\"date\":{},\"ghost_index\":{}}}",
+                        entry.rank(),
+                        json_string(&entry.player().to_string()),
+                        json_string(entry.name()),
+                        json_string(&entry.time().to_string()),
+                        json_option(entry.exact_time().map(|time| json_string(&time.to_string()))),
+                        json_option(entry.lap().map(|lap| lap.to_string())),
+                        json_string(&format!("{:?}", entry.character())),
+                        json_string(&format!("{:?}", entry.vehicle())),
+                        json_string(&date(entry)),
+                        entry.ghost_index(),
+                    )
+                })
+                .collect();
+
+            format!(
+                "{{\"board\":{},\"track\":{},\"track_sha1\":{},\"category\":{},\"entries\":[{}]}}",
+                json_string(&board.kind().to_string()),
+                json_string(&format!("{:?}", board.slot_id())),
+                json_option(board.track_sha1().map(|sha1| json_string(&hex(sha1)))),
+                json_option(
+                    board
+                        .category()
+                        .map(|category| json_string(&format!("{category:?}")))
+                ),
+                entries.join(","),
+            )
+        })
+        .collect();
+
+    format!("[{}]", boards.join(","))
+}
+
+/// One row per entry of every board, with a header row. Empty fields stand for unknown values.
+pub fn to_csv(boards: &[Leaderboard]) -> String {
+    let mut csv = CSV_COLUMNS.join(",");
+    csv.push_str("\r\n");
+
+    for board in boards {
+        for entry in board.entries() {
+            let row = [
+                board.kind().to_string(),
+                format!("{:?}", board.slot_id()),
+                board.track_sha1().map(|sha1| hex(sha1)).unwrap_or_default(),
+                board
+                    .category()
+                    .map(|category| format!("{category:?}"))
+                    .unwrap_or_default(),
+                entry.rank().to_string(),
+                entry.player().to_string(),
+                String::from(entry.name()),
+                entry.time().to_string(),
+                entry
+                    .exact_time()
+                    .map(|time| time.to_string())
+                    .unwrap_or_default(),
+                entry.lap().map(|lap| lap.to_string()).unwrap_or_default(),
+                format!("{:?}", entry.character()),
+                format!("{:?}", entry.vehicle()),
+                date(entry),
+            ];
+
+            let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push_str("\r\n");
+        }
+    }
+
+    csv
+}
+
+/// A heading and a table per board
+pub fn to_markdown(boards: &[Leaderboard]) -> String {
+    let mut markdown = String::new();
+
+    for board in boards {
+        if !markdown.is_empty() {
+            markdown.push('\n');
+        }
+
+        let category = board
+            .category()
+            .map(|category| format!("{category:?}"))
+            .unwrap_or_else(|| String::from("No category"));
+        let _ = write!(markdown, "## {:?}, {category}", board.slot_id());
+        if let Some(sha1) = board.track_sha1() {
+            let _ = write!(markdown, " (`{}`)", hex(sha1));
+        }
+        let _ = writeln!(markdown, ", {}\n", board.kind());
+
+        markdown.push_str("| Rank | Player | Time | Exact time | Lap | Combo | Date |\n");
+        markdown.push_str("| ---: | --- | --- | --- | ---: | --- | --- |\n");
+        for entry in board.entries() {
+            let _ = writeln!(
+                markdown,
+                "| {} | {} | {} | {} | {} | {:?} / {:?} | {} |",
+                entry.rank(),
+                markdown_cell(entry.name()),
+                entry.time(),
+                entry
+                    .exact_time()
+                    .map(|time| time.to_string())
+                    .unwrap_or_default(),
+                entry.lap().map(|lap| lap.to_string()).unwrap_or_default(),
+                entry.character(),
+                entry.vehicle(),
+                date(entry),
+            );
+        }
+    }
+
+    markdown
+}
+
+fn date(entry: &LeaderboardEntry) -> String {
+    let (year, month, day) = entry.date();
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn json_option(value: Option<String>) -> String {
+    value.unwrap_or_else(|| String::from("null"))
+}
+
+fn json_string(value: &str) -> String {
+    let mut json = String::from("\"");
+
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c < ' ' => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+/// Quotes fields that would otherwise break the row, per RFC 4180
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        String::from(value)
+    }
+}
+
+/// Mii names can hold anything, keep them from closing the cell or starting markup
+fn markdown_cell(value: &str) -> String {
+    let mut cell = String::new();
+
+    for c in value.chars() {
+        match c {
+            '|' | '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' => {
+                cell.push('\\');
+                cell.push(c);
+            }
+            '\r' | '\n' => cell.push(' '),
+            c => cell.push(c),
+        }
+    }
+
+    cell
+}
@@ -0,0 +1,288 @@
+//! Leaderboards like the ones community sites keep: one board per track and category, holding
+//! the best time of every player.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::Display;
+
+use crate::{
+    Ghost,
+    ctgp_metadata::{category::Category, exact_finish_time::ExactFinishTime},
+    header::{
+        combo::{Character, Vehicle},
+        in_game_time::InGameTime,
+        mii::{mii_id::MiiId, system_id::SystemId},
+        slot_id::SlotId,
+    },
+};
+
+pub mod export;
+
+/// Which time a board ranks
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BoardKind {
+    /// Whole race
+    Finish,
+    /// Fastest single lap of a race
+    FastestLap,
+}
+
+impl Display for BoardKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Finish => write!(f, "finish"),
+            Self::FastestLap => write!(f, "flap"),
+        }
+    }
+}
+
+/// Who set a time. CTGP ghosts carry the player's account ID, other ghosts only their Mii, which
+/// is tied to the console it was made on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PlayerKey {
+    Ctgp(u64),
+    Mii { system_id: SystemId, mii_id: MiiId },
+}
+
+impl PlayerKey {
+    pub fn of(ghost: &Ghost) -> Self {
+        match ghost.ctgp_metadata() {
+            Some(metadata) => Self::Ctgp(metadata.player_id()),
+            None => Self::Mii {
+                system_id: ghost.header().mii().system_id(),
+                mii_id: ghost.header().mii().mii_id(),
+            },
+        }
+    }
+}
+
+impl Display for PlayerKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Ctgp(player_id) => write!(f, "ctgp:{player_id:016X}"),
+            Self::Mii { system_id, mii_id } => {
+                write!(f, "mii:{:08X}-{:08X}", system_id.raw(), mii_id.raw())
+            }
+        }
+    }
+}
+
+/// A player's best time on a board
+#[derive(Clone, Debug)]
+pub struct LeaderboardEntry {
+    rank: usize,
+    player: PlayerKey,
+    name: String,
+    time: InGameTime,
+    exact_time: Option<ExactFinishTime>,
+    lap: Option<u8>,
+    character: Character,
+    vehicle: Vehicle,
+    date: (u16, u8, u8),
+    ghost_index: usize,
+}
+
+impl LeaderboardEntry {
+    /// Starts at 1, tied times share a rank
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    pub fn player(&self) -> PlayerKey {
+        self.player
+    }
+
+    /// Mii name of the ghost the time comes from
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn time(&self) -> InGameTime {
+        self.time
+    }
+
+    /// Only known for ghosts with a CTGP footer
+    pub fn exact_time(&self) -> Option<ExactFinishTime> {
+        self.exact_time
+    }
+
+    /// Lap the time was set on, counting from 1. `None` on finish boards.
+    pub fn lap(&self) -> Option<u8> {
+        self.lap
+    }
+
+    pub fn character(&self) -> Character {
+        self.character
+    }
+
+    pub fn vehicle(&self) -> Vehicle {
+        self.vehicle
+    }
+
+    /// `(year, month, day)` the ghost was set
+    pub fn date(&self) -> (u16, u8, u8) {
+        self.date
+    }
+
+    /// Index of the ghost in the slice the board was built from
+    pub fn ghost_index(&self) -> usize {
+        self.ghost_index
+    }
+
+    /// Exact time first, in-game time for ties. Ghosts without a footer use their in-game time
+    /// as exact time.
+    fn sort_key(&self) -> (ExactFinishTime, InGameTime) {
+        (
+            self.exact_time
+                .unwrap_or_else(|| ExactFinishTime::from(self.time)),
+            self.time,
+        )
+    }
+}
+
+/// Ranked times of one track and category. Custom tracks sharing a slot are told apart by the
+/// SHA1 from their CTGP footer, ghosts without a footer have neither SHA1 nor category.
+#[derive(Clone, Debug)]
+pub struct Leaderboard {
+    kind: BoardKind,
+    slot_id: SlotId,
+    track_sha1: Option<[u8; 0x14]>,
+    category: Option<Category>,
+    entries: Vec<LeaderboardEntry>,
+}
+
+impl Leaderboard {
+    /// Builds a board for every track and category in `ghosts`, in the order they first appear.
+    /// Each player only keeps their best time.
+    pub fn build(ghosts: &[Ghost], kind: BoardKind) -> Vec<Self> {
+        let mut boards: Vec<Self> = Vec::new();
+
+        for (ghost_index, ghost) in ghosts.iter().enumerate() {
+            let Some(entry) = entry_of(ghost, ghost_index, kind) else {
+                continue;
+            };
+
+            let header = ghost.header();
+            let metadata = ghost.ctgp_metadata();
+            let slot_id = header.slot_id();
+            let track_sha1 = metadata.and_then(|metadata| metadata.track_sha1().try_into().ok());
+            let category = metadata.map(|metadata| metadata.category());
+
+            let board_index = match boards.iter().position(|board| {
+                board.slot_id == slot_id
+                    && board.track_sha1 == track_sha1
+                    && board.category == category
+            }) {
+                Some(index) => index,
+                None => {
+                    boards.push(Self {
+                        kind,
+                        slot_id,
+                        track_sha1,
+                        category,
+                        entries: Vec::new(),
+                    });
+                    boards.len() - 1
+                }
+            };
+
+            let entries = &mut boards[board_index].entries;
+            match entries
+                .iter_mut()
+                .find(|other| other.player == entry.player)
+            {
+                Some(best) if entry.sort_key() < best.sort_key() => *best = entry,
+                Some(_) => (),
+                None => entries.push(entry),
+            }
+        }
+
+        for board in &mut boards {
+            board.rank();
+        }
+
+        boards
+    }
+
+    fn rank(&mut self) {
+        // Stable, so equal times keep the order their ghosts were given in
+        self.entries.sort_by_key(LeaderboardEntry::sort_key);
+
+        for index in 0..self.entries.len() {
+            self.entries[index].rank = match index.checked_sub(1) {
+                Some(previous)
+                    if self.entries[previous].sort_key() == self.entries[index].sort_key() =>
+                {
+                    self.entries[previous].rank
+                }
+                _ => index + 1,
+            };
+        }
+    }
+
+    pub fn kind(&self) -> BoardKind {
+        self.kind
+    }
+
+    pub fn slot_id(&self) -> SlotId {
+        self.slot_id
+    }
+
+    pub fn track_sha1(&self) -> Option<&[u8; 0x14]> {
+        self.track_sha1.as_ref()
+    }
+
+    pub fn category(&self) -> Option<Category> {
+        self.category
+    }
+
+    /// Fastest first
+    pub fn entries(&self) -> &[LeaderboardEntry] {
+        &self.entries
+    }
+}
+
+/// The time `ghost` puts on a board of `kind`. `None` for a flap board if no lap was driven.
+fn entry_of(ghost: &Ghost, ghost_index: usize, kind: BoardKind) -> Option<LeaderboardEntry> {
+    let header = ghost.header();
+    let metadata = ghost.ctgp_metadata();
+
+    let (time, exact_time, lap) = match kind {
+        BoardKind::Finish => (
+            *header.finish_time(),
+            metadata.map(|metadata| metadata.exact_finish_time()),
+            None,
+        ),
+        BoardKind::FastestLap => {
+            let exact_lap_times = metadata.map(|metadata| metadata.exact_lap_times());
+            let exact_time_of =
+                |lap: usize| exact_lap_times.and_then(|times| times.get(lap).copied());
+
+            let (lap, time) =
+                header
+                    .lap_split_times()
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(lap, time)| {
+                        (
+                            exact_time_of(*lap).unwrap_or(ExactFinishTime::from(**time)),
+                            **time,
+                        )
+                    })?;
+            (*time, exact_time_of(lap), Some(lap as u8 + 1))
+        }
+    };
+
+    let date = header.date_set();
+    Some(LeaderboardEntry {
+        rank: 0,
+        player: PlayerKey::of(ghost),
+        name: String::from(header.mii().name()),
+        time,
+        exact_time,
+        lap,
+        character: header.combo().character(),
+        vehicle: header.combo().vehicle(),
+        date: (date.year(), date.month(), date.day()),
+        ghost_index,
+    })
+}
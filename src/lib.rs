@@ -1,9 +1,33 @@
-use std::io::Read;
+#![no_std]
 
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
+
+pub mod audit;
 pub mod byte_handler;
+#[cfg(feature = "capi")]
+pub mod capi;
+mod crc;
 pub mod ctgp_metadata;
 pub mod header;
+pub mod identity;
 pub mod input_data;
+pub mod integrity;
+pub mod layout;
+pub mod leaderboard;
+#[cfg(feature = "library")]
+pub mod library;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod save;
+pub mod timing;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 /*
  * TODO:
@@ -13,32 +37,113 @@ pub mod input_data;
  * Read CTGP pause times
  * Document and handle older CTGP footer versions
  * Be able to modify variables in ghost files
- * Implement TryFrom<_> for T where T: Into<ByteHandler>, relies on https://github.com/rust-lang/rust/issues/31844 currently
- * Represent at a Type-system level which types can convert from T to TypeHandler to whichever Struct
- * Optimize Little-Endian calculations
- * Figure out whether Big-Endian works
  */
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests;
 
-struct Ghost {
+#[derive(thiserror::Error, Debug)]
+pub enum GhostError {
+    #[error("Data is too short to be a ghost")]
+    TooShort,
+    #[error("Header Error: {0}")]
+    HeaderError(#[from] header::HeaderError),
+    #[error("Input Data Error: {0}")]
+    InputDataError(#[from] input_data::InputDataError),
+    #[error("CTGP Metadata Error: {0}")]
+    CTGPMetadataError(#[from] ctgp_metadata::CTGPMetadataError),
+    #[cfg(feature = "std")]
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// A whole RKG file, with its CTGP footer if it has one
+#[derive(Clone)]
+pub struct Ghost {
     header: header::Header,
     input_data: input_data::InputData,
     ctgp_metadata: Option<ctgp_metadata::CTGPMetadata>,
+    bytes: Vec<u8>,
 }
 
 impl Ghost {
-    fn new_from_file<T: AsRef<std::path::Path>>(path: T) -> Self {
-        let mut buf = Vec::with_capacity(0x100);
-        std::fs::File::open(path)
-            .unwrap()
-            .read_to_end(&mut buf)
-            .unwrap();
-        Self::new(&buf)
+    #[cfg(feature = "std")]
+    pub fn new_from_file<T: AsRef<std::path::Path>>(path: T) -> Result<Self, GhostError> {
+        Self::read_from(std::io::BufReader::new(std::fs::File::open(path)?))
+    }
+
+    /// Reads a ghost from a stream without seeking. The header says how long the input data is,
+    /// so the RKG part is read exactly. A CTGP footer only stores its size at its very end, so
+    /// everything after the RKG's CRC32 is read until the end of the stream and treated as one.
+    #[cfg(feature = "std")]
+    pub fn read_from<R: Read>(mut reader: R) -> Result<Self, GhostError> {
+        let mut bytes = alloc::vec![0u8; 0x88];
+        reader.read_exact(&mut bytes)?;
+        let header = header::Header::new(&bytes)?;
+        bytes.extend(input_data::read_input_section(&mut reader, &header)?);
+
+        let mut crc32 = [0u8; 0x04];
+        reader.read_exact(&mut crc32)?;
+        bytes.extend_from_slice(&crc32);
+
+        // Vanilla ghosts end here, anything else has to be a CTGP footer
+        reader.read_to_end(&mut bytes)?;
+        Self::new(&bytes)
+    }
+
+    /// Writes the ghost exactly as it was read
+    #[cfg(feature = "std")]
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), GhostError> {
+        writer.write_all(&self.bytes)?;
+        Ok(())
+    }
+
+    pub fn new(bytes: &[u8]) -> Result<Self, GhostError> {
+        // Header, input counts and CRC32
+        if bytes.len() < 0x88 + 0x08 + 0x04 {
+            return Err(GhostError::TooShort);
+        }
+
+        let header = header::Header::new(&bytes[..0x88])?;
+        let input_data = input_data::InputData::new(&bytes[0x88..])?;
+        let ctgp_metadata = if bytes[bytes.len() - 0x08..bytes.len() - 0x04] == *b"CKGD" {
+            Some(ctgp_metadata::CTGPMetadata::new(bytes)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            header,
+            input_data,
+            ctgp_metadata,
+            bytes: Vec::from(bytes),
+        })
+    }
+
+    pub fn header(&self) -> &header::Header {
+        &self.header
+    }
+
+    pub fn input_data(&self) -> &input_data::InputData {
+        &self.input_data
+    }
+
+    pub fn ctgp_metadata(&self) -> Option<&ctgp_metadata::CTGPMetadata> {
+        self.ctgp_metadata.as_ref()
+    }
+
+    /// The file the ghost was read from
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Checks CRCs, lengths and the CTGP footer size, see `integrity::verify_integrity`
+    pub fn verify_integrity(&self) -> integrity::IntegrityReport {
+        integrity::verify_integrity(&self.bytes)
     }
 
-    fn new(_bytes: &[u8]) -> Self {
-        todo!()
+    /// Runs every available check on the ghost, see `audit::audit`
+    pub fn audit(&self) -> audit::AuditReport {
+        audit::audit(&self.bytes)
     }
 }
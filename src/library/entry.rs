@@ -0,0 +1,169 @@
+use alloc::{format, string::String};
+use rusqlite::{Row, types::Value};
+
+use crate::{
+    ctgp_metadata::{category::Category, exact_finish_time::ExactFinishTime},
+    header::{
+        combo::{Character, Vehicle},
+        controller::Controller,
+        in_game_time::InGameTime,
+        slot_id::SlotId,
+    },
+};
+
+/// Columns `LibraryEntry::from_row` expects, in order
+pub(crate) const ENTRY_COLUMNS: &str = "hash, slot_id, track_sha1, finish_time_ms, \
+    exact_finish_time_ps, character, vehicle, controller, category, player_id, mii_name, \
+    mii_id, system_id, year, month, day";
+
+/// What the library knows about a ghost without reading its file
+#[derive(Clone, Debug)]
+pub struct LibraryEntry {
+    hash: String,
+    slot_id: SlotId,
+    track_sha1: Option<[u8; 0x14]>,
+    finish_time: InGameTime,
+    exact_finish_time: Option<ExactFinishTime>,
+    character: Character,
+    vehicle: Vehicle,
+    controller: Controller,
+    category: Option<Category>,
+    player_id: Option<u64>,
+    mii_name: String,
+    mii_id: u32,
+    system_id: u32,
+    date: (u16, u8, u8),
+}
+
+impl LibraryEntry {
+    pub(crate) fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        let slot_id: u8 = row.get(1)?;
+        let finish_time_ms: u32 = row.get(3)?;
+        let exact_finish_time_ps: Option<i64> = row.get(4)?;
+        let character: u8 = row.get(5)?;
+        let vehicle: u8 = row.get(6)?;
+        let controller: u8 = row.get(7)?;
+        let category: Option<String> = row.get(8)?;
+        let player_id: Option<i64> = row.get(9)?;
+
+        Ok(Self {
+            hash: row.get(0)?,
+            slot_id: SlotId::try_from(slot_id).map_err(|_| unknown_value(1, slot_id))?,
+            track_sha1: row.get(2)?,
+            finish_time: InGameTime::from_millis(finish_time_ms)
+                .map_err(|_| unknown_value(3, finish_time_ms))?,
+            exact_finish_time: exact_finish_time_ps
+                .map(|picoseconds| {
+                    ExactFinishTime::from_picoseconds(picoseconds as u64)
+                        .map_err(|_| unknown_value(4, picoseconds))
+                })
+                .transpose()?,
+            character: Character::try_from(character).map_err(|_| unknown_value(5, character))?,
+            vehicle: Vehicle::try_from(vehicle).map_err(|_| unknown_value(6, vehicle))?,
+            controller: Controller::try_from(controller)
+                .map_err(|_| unknown_value(7, controller))?,
+            category: category
+                .map(|name| category_from_name(&name).ok_or_else(|| unknown_value(8, name)))
+                .transpose()?,
+            player_id: player_id.map(|player_id| player_id as u64),
+            mii_name: row.get(10)?,
+            mii_id: row.get(11)?,
+            system_id: row.get(12)?,
+            date: (row.get(13)?, row.get(14)?, row.get(15)?),
+        })
+    }
+
+    /// Hex SHA1 of the file, its name in the store
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    pub fn slot_id(&self) -> SlotId {
+        self.slot_id
+    }
+
+    /// SHA1 of the track file from the CTGP footer, tells custom tracks sharing a slot apart
+    pub fn track_sha1(&self) -> Option<&[u8; 0x14]> {
+        self.track_sha1.as_ref()
+    }
+
+    pub fn finish_time(&self) -> InGameTime {
+        self.finish_time
+    }
+
+    pub fn exact_finish_time(&self) -> Option<ExactFinishTime> {
+        self.exact_finish_time
+    }
+
+    pub fn character(&self) -> Character {
+        self.character
+    }
+
+    pub fn vehicle(&self) -> Vehicle {
+        self.vehicle
+    }
+
+    pub fn controller(&self) -> Controller {
+        self.controller
+    }
+
+    /// Only known for ghosts with a CTGP footer
+    pub fn category(&self) -> Option<Category> {
+        self.category
+    }
+
+    pub fn player_id(&self) -> Option<u64> {
+        self.player_id
+    }
+
+    pub fn mii_name(&self) -> &str {
+        &self.mii_name
+    }
+
+    pub fn mii_id(&self) -> u32 {
+        self.mii_id
+    }
+
+    pub fn system_id(&self) -> u32 {
+        self.system_id
+    }
+
+    /// `(year, month, day)` the ghost was set
+    pub fn date(&self) -> (u16, u8, u8) {
+        self.date
+    }
+}
+
+/// Error for a column holding a value none of our types have, like rusqlite's own conversion errors
+fn unknown_value(index: usize, value: impl Into<Value>) -> rusqlite::Error {
+    let value = value.into();
+    rusqlite::Error::FromSqlConversionFailure(
+        index,
+        value.data_type(),
+        format!("{value:?} isn't a known value").into(),
+    )
+}
+
+pub(crate) fn category_name(category: Category) -> &'static str {
+    match category {
+        Category::NoShortcut => "NoShortcut",
+        Category::Shortcut => "Shortcut",
+        Category::Glitch => "Glitch",
+        Category::NoShortcutTAS => "NoShortcutTAS",
+        Category::ShortcutTAS => "ShortcutTAS",
+        Category::GlitchTAS => "GlitchTAS",
+    }
+}
+
+fn category_from_name(name: &str) -> Option<Category> {
+    [
+        Category::NoShortcut,
+        Category::Shortcut,
+        Category::Glitch,
+        Category::NoShortcutTAS,
+        Category::ShortcutTAS,
+        Category::GlitchTAS,
+    ]
+    .into_iter()
+    .find(|category| category_name(*category) == name)
+}
@@ -0,0 +1,293 @@
+//! A local ghost library: files are stored once under the SHA1 of their contents, and what they
+//! contain is indexed in SQLite so collections of tens of thousands of ghosts can be queried
+//! without reading them again.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::{Ghost, GhostError, ctgp_metadata::category::Category, header::slot_id::SlotId};
+
+pub mod entry;
+
+pub use entry::LibraryEntry;
+use entry::{ENTRY_COLUMNS, category_name};
+
+#[derive(thiserror::Error, Debug)]
+pub enum LibraryError {
+    #[error("Ghost Error: {0}")]
+    GhostError(#[from] GhostError),
+    #[error("SQLite Error: {0}")]
+    SqliteError(#[from] rusqlite::Error),
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Ghost {0} isn't in the library")]
+    NotFound(String),
+}
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS ghosts (
+    hash TEXT PRIMARY KEY NOT NULL,
+    slot_id INTEGER NOT NULL,
+    track_sha1 BLOB,
+    finish_time_ms INTEGER NOT NULL,
+    exact_finish_time_ps INTEGER,
+    character INTEGER NOT NULL,
+    vehicle INTEGER NOT NULL,
+    controller INTEGER NOT NULL,
+    category TEXT,
+    player_id INTEGER,
+    mii_name TEXT NOT NULL,
+    mii_id INTEGER NOT NULL,
+    system_id INTEGER NOT NULL,
+    year INTEGER NOT NULL,
+    month INTEGER NOT NULL,
+    day INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS ghosts_by_track
+    ON ghosts (slot_id, track_sha1, category, finish_time_ms, exact_finish_time_ps);
+CREATE INDEX IF NOT EXISTS ghosts_by_player ON ghosts (player_id);
+CREATE INDEX IF NOT EXISTS ghosts_by_mii ON ghosts (system_id, mii_id);
+";
+
+/// Ordering of ghosts from fastest to slowest, exact times break in-game time ties
+const FASTEST_FIRST: &str = "finish_time_ms, exact_finish_time_ps IS NULL, exact_finish_time_ps";
+
+/// Whether `GhostLibrary::import` added a ghost or already had it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportOutcome {
+    Added,
+    AlreadyPresent,
+}
+
+/// Hash of an imported ghost and whether it was new
+pub type ImportResult = Result<(String, ImportOutcome), LibraryError>;
+
+/// A directory holding `library.sqlite3` and the ghosts themselves in `objects/`, named like
+/// `objects/ab/cdef...rkg` after the SHA1 of the file
+pub struct GhostLibrary {
+    root: PathBuf,
+    connection: Connection,
+}
+
+impl GhostLibrary {
+    /// Opens the library at `root`, creating it if needed
+    pub fn open<P: AsRef<Path>>(root: P) -> Result<Self, LibraryError> {
+        let root = root.as_ref().to_path_buf();
+        std::fs::create_dir_all(root.join("objects"))?;
+
+        let connection = Connection::open(root.join("library.sqlite3"))?;
+        connection.execute_batch(SCHEMA)?;
+
+        Ok(Self { root, connection })
+    }
+
+    /// Stores and indexes a ghost file. Identical files are only stored once. The file only gets
+    /// its final name once the index is committed, so a failed import leaves neither behind.
+    pub fn import(&mut self, bytes: &[u8]) -> ImportResult {
+        let ghost = Ghost::new(bytes)?;
+        let hash = sha1_smol::Sha1::from(bytes).digest().to_string();
+
+        if self.contains(&hash)? {
+            return Ok((hash, ImportOutcome::AlreadyPresent));
+        }
+
+        let path = self.object_path(&hash);
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        let temporary_path = path.with_extension("rkg.tmp");
+        std::fs::write(&temporary_path, bytes)?;
+
+        let transaction = self.connection.transaction()?;
+        let indexed = Self::index(&transaction, &hash, &ghost).and_then(|()| {
+            transaction.commit()?;
+            std::fs::rename(&temporary_path, &path)?;
+            Ok(())
+        });
+        if let Err(error) = indexed {
+            // Undo whichever steps got through, errors here would only hide the original one
+            let _ = std::fs::remove_file(&temporary_path);
+            let _ = self
+                .connection
+                .execute("DELETE FROM ghosts WHERE hash = ?1", [&hash]);
+            return Err(error);
+        }
+
+        Ok((hash, ImportOutcome::Added))
+    }
+
+    pub fn import_file<P: AsRef<Path>>(&mut self, path: P) -> ImportResult {
+        self.import(&std::fs::read(path)?)
+    }
+
+    /// Imports every `.rkg` file directly in `directory`. Files that aren't valid ghosts are
+    /// returned with their error instead of stopping the import.
+    pub fn import_directory<P: AsRef<Path>>(
+        &mut self,
+        directory: P,
+    ) -> Result<Vec<(PathBuf, ImportResult)>, LibraryError> {
+        let mut results = Vec::new();
+
+        for entry in std::fs::read_dir(directory)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == "rkg") {
+                let result = self.import_file(&path);
+                results.push((path, result));
+            }
+        }
+
+        Ok(results)
+    }
+
+    pub fn contains(&self, hash: &str) -> Result<bool, LibraryError> {
+        Ok(self
+            .connection
+            .query_row("SELECT 1 FROM ghosts WHERE hash = ?1", [hash], |_| Ok(()))
+            .optional()?
+            .is_some())
+    }
+
+    pub fn len(&self) -> Result<usize, LibraryError> {
+        Ok(self
+            .connection
+            .query_row("SELECT COUNT(*) FROM ghosts", [], |row| row.get(0))?)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, LibraryError> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Reads a stored ghost back
+    pub fn ghost(&self, hash: &str) -> Result<Ghost, LibraryError> {
+        if !self.contains(hash)? {
+            return Err(LibraryError::NotFound(hash.to_string()));
+        }
+
+        Ok(Ghost::new_from_file(self.object_path(hash))?)
+    }
+
+    pub fn entry(&self, hash: &str) -> Result<Option<LibraryEntry>, LibraryError> {
+        let query = format!("SELECT {ENTRY_COLUMNS} FROM ghosts WHERE hash = ?1");
+        Ok(self
+            .connection
+            .query_row(&query, [hash], LibraryEntry::from_row)
+            .optional()?)
+    }
+
+    /// The `count` fastest ghosts of every track and category, fastest first. Custom tracks
+    /// sharing a slot are told apart by their SHA1, ghosts without a CTGP footer form their own
+    /// category. With `category`, only that category is returned.
+    pub fn best_per_track(
+        &self,
+        count: usize,
+        category: Option<Category>,
+    ) -> Result<Vec<LibraryEntry>, LibraryError> {
+        let query = format!(
+            "SELECT {ENTRY_COLUMNS} FROM (
+                SELECT *, ROW_NUMBER() OVER (
+                    PARTITION BY slot_id, track_sha1, category ORDER BY {FASTEST_FIRST}
+                ) AS rank
+                FROM ghosts
+                WHERE ?2 IS NULL OR category = ?2
+            )
+            WHERE rank <= ?1
+            ORDER BY slot_id, track_sha1, category, rank"
+        );
+        let mut statement = self.connection.prepare(&query)?;
+        let entries = statement
+            .query_map(
+                params![count as i64, category.map(category_name)],
+                LibraryEntry::from_row,
+            )?
+            .collect::<Result<_, _>>()?;
+
+        Ok(entries)
+    }
+
+    /// Every ghost of a track, fastest first
+    pub fn by_track(&self, slot_id: SlotId) -> Result<Vec<LibraryEntry>, LibraryError> {
+        self.select(
+            &format!("slot_id = ?1 ORDER BY {FASTEST_FIRST}"),
+            params![u8::from(slot_id)],
+        )
+    }
+
+    /// Every ghost with this CTGP player ID, oldest first
+    pub fn by_player(&self, player_id: u64) -> Result<Vec<LibraryEntry>, LibraryError> {
+        self.select(
+            "player_id = ?1 ORDER BY year, month, day",
+            params![player_id as i64],
+        )
+    }
+
+    /// Every ghost driven with this Mii, whatever it was called at the time, oldest first
+    pub fn by_mii(&self, system_id: u32, mii_id: u32) -> Result<Vec<LibraryEntry>, LibraryError> {
+        self.select(
+            "system_id = ?1 AND mii_id = ?2 ORDER BY year, month, day",
+            params![system_id, mii_id],
+        )
+    }
+
+    fn select(
+        &self,
+        condition: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<LibraryEntry>, LibraryError> {
+        let query = format!("SELECT {ENTRY_COLUMNS} FROM ghosts WHERE {condition}");
+        let mut statement = self.connection.prepare(&query)?;
+        let entries = statement
+            .query_map(params, LibraryEntry::from_row)?
+            .collect::<Result<_, _>>()?;
+
+        Ok(entries)
+    }
+
+    fn index(connection: &Connection, hash: &str, ghost: &Ghost) -> Result<(), LibraryError> {
+        let header = ghost.header();
+        let ctgp_metadata = ghost.ctgp_metadata();
+        let mii = header.mii();
+        let date = header.date_set();
+
+        connection.execute(
+            &format!(
+                "INSERT INTO ghosts ({ENTRY_COLUMNS})
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)"
+            ),
+            params![
+                hash,
+                u8::from(header.slot_id()),
+                ctgp_metadata.map(|metadata| metadata.track_sha1()),
+                header.finish_time().igt_to_millis(),
+                ctgp_metadata
+                    .map(|metadata| metadata.exact_finish_time().total_picoseconds() as i64),
+                u8::from(header.combo().character()),
+                u8::from(header.combo().vehicle()),
+                u8::from(header.controller()),
+                ctgp_metadata.map(|metadata| category_name(metadata.category())),
+                ctgp_metadata.map(|metadata| metadata.player_id() as i64),
+                mii.name(),
+                mii.mii_id().raw(),
+                mii.system_id().raw(),
+                date.year(),
+                date.month(),
+                date.day(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        let (directory, name) = hash.split_at(2);
+        self.root
+            .join("objects")
+            .join(directory)
+            .join(format!("{name}.rkg"))
+    }
+}
@@ -0,0 +1,386 @@
+//! PyO3 classes mirroring `Ghost`, `Header`, `Mii`, `InputData` and `CTGPMetadata`. The extension
+//! module is built from the `python/` companion crate with `maturin develop`, then `import rkg_utils`.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use pyo3::{create_exception, exceptions::PyValueError, prelude::*, types::PyBytes};
+
+use crate::header::mii::Mii;
+
+create_exception!(
+    rkg_utils,
+    RkgError,
+    PyValueError,
+    "Raised when a ghost or one of its sections can't be parsed"
+);
+
+fn to_py_err(err: impl ToString) -> PyErr {
+    RkgError::new_err(err.to_string())
+}
+
+/// A whole RKG file, with its CTGP footer if it has one
+#[pyclass(name = "Ghost", module = "rkg_utils", frozen)]
+pub struct PyGhost(crate::Ghost);
+
+#[pymethods]
+impl PyGhost {
+    #[new]
+    fn new(data: &[u8]) -> PyResult<Self> {
+        crate::Ghost::new(data).map(Self).map_err(to_py_err)
+    }
+
+    #[staticmethod]
+    fn from_file(path: std::path::PathBuf) -> PyResult<Self> {
+        crate::Ghost::new_from_file(path)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    #[getter]
+    fn header(&self) -> PyHeader {
+        PyHeader(self.0.header().clone())
+    }
+
+    #[getter]
+    fn input_data(&self) -> PyInputData {
+        PyInputData(self.0.input_data().clone())
+    }
+
+    #[getter]
+    fn ctgp_metadata(&self) -> Option<PyCTGPMetadata> {
+        self.0.ctgp_metadata().cloned().map(PyCTGPMetadata)
+    }
+
+    fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, self.0.bytes())
+    }
+
+    /// Structural problems of the file, empty if it's intact
+    fn verify_integrity(&self) -> Vec<String> {
+        self.0
+            .verify_integrity()
+            .issues()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// `(code, severity, message)` of every audit finding
+    fn audit(&self) -> Vec<(&'static str, String, String)> {
+        self.0
+            .audit()
+            .findings()
+            .iter()
+            .map(|finding| {
+                (
+                    finding.code(),
+                    finding.severity().to_string(),
+                    finding.message().to_string(),
+                )
+            })
+            .collect()
+    }
+}
+
+/// The 0x88 byte header of a ghost
+#[pyclass(name = "Header", module = "rkg_utils", frozen)]
+pub struct PyHeader(crate::header::Header);
+
+#[pymethods]
+impl PyHeader {
+    #[new]
+    fn new(data: &[u8]) -> PyResult<Self> {
+        crate::header::Header::new(data)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    /// `"mm:ss.mmm"`
+    #[getter]
+    fn finish_time(&self) -> String {
+        self.0.finish_time().to_string()
+    }
+
+    #[getter]
+    fn finish_time_ms(&self) -> i32 {
+        self.0.finish_time().igt_to_millis()
+    }
+
+    /// Only the laps that were driven
+    #[getter]
+    fn lap_split_times(&self) -> Vec<String> {
+        self.0
+            .lap_split_times()
+            .iter()
+            .take(self.0.lap_count() as usize)
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[getter]
+    fn lap_count(&self) -> u8 {
+        self.0.lap_count()
+    }
+
+    #[getter]
+    fn track(&self) -> String {
+        format!("{:?}", self.0.slot_id())
+    }
+
+    #[getter]
+    fn character(&self) -> String {
+        format!("{:?}", self.0.combo().character())
+    }
+
+    #[getter]
+    fn vehicle(&self) -> String {
+        format!("{:?}", self.0.combo().vehicle())
+    }
+
+    #[getter]
+    fn controller(&self) -> String {
+        format!("{:?}", self.0.controller())
+    }
+
+    #[getter]
+    fn ghost_type(&self) -> String {
+        format!("{:?}", self.0.ghost_type())
+    }
+
+    /// `(year, month, day)`
+    #[getter]
+    fn date(&self) -> (u16, u8, u8) {
+        let date = self.0.date_set();
+        (date.year(), date.month(), date.day())
+    }
+
+    #[getter]
+    fn is_compressed(&self) -> bool {
+        self.0.is_compressed()
+    }
+
+    #[getter]
+    fn is_automatic_drift(&self) -> bool {
+        self.0.is_automatic_drift()
+    }
+
+    #[getter]
+    fn country_id(&self) -> u8 {
+        self.0.country_id()
+    }
+
+    #[getter]
+    fn mii(&self) -> PyMii {
+        PyMii(self.0.mii().clone())
+    }
+}
+
+/// The Mii stored in a header
+#[pyclass(name = "Mii", module = "rkg_utils", frozen)]
+pub struct PyMii(Mii);
+
+#[pymethods]
+impl PyMii {
+    #[new]
+    fn new(data: &[u8]) -> PyResult<Self> {
+        Mii::new(data).map(Self).map_err(to_py_err)
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    /// Name with the Wii font's own symbols replaced by Unicode ones
+    #[getter]
+    fn display_name(&self) -> String {
+        self.0.display_name()
+    }
+
+    #[getter]
+    fn creator_name(&self) -> &str {
+        self.0.creator_name()
+    }
+
+    #[getter]
+    fn is_girl(&self) -> bool {
+        self.0.is_girl()
+    }
+
+    #[getter]
+    fn favorite_color(&self) -> String {
+        format!("{:?}", self.0.favorite_color())
+    }
+
+    #[getter]
+    fn mii_id(&self) -> u32 {
+        self.0.mii_id().raw()
+    }
+
+    #[getter]
+    fn system_id(&self) -> u32 {
+        self.0.system_id().raw()
+    }
+}
+
+/// The controller inputs of a ghost
+#[pyclass(name = "InputData", module = "rkg_utils", frozen)]
+pub struct PyInputData(crate::input_data::InputData);
+
+#[pymethods]
+impl PyInputData {
+    /// Takes everything after the header, compressed or not
+    #[new]
+    fn new(data: &[u8]) -> PyResult<Self> {
+        crate::input_data::InputData::new(data)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    /// Layout of a `frame_inputs()` record, as a NumPy structured dtype
+    #[classattr]
+    #[allow(non_snake_case)]
+    fn FRAME_DTYPE() -> Vec<(&'static str, &'static str)> {
+        Vec::from(FRAME_DTYPE)
+    }
+
+    #[getter]
+    fn frame_count(&self) -> u32 {
+        self.0.frame_count()
+    }
+
+    /// Runs of identical inputs as `(buttons, stick_x, stick_y, dpad, frames)`
+    fn inputs(&self) -> Vec<(Vec<String>, i8, i8, String, u32)> {
+        self.0
+            .inputs()
+            .iter()
+            .map(|input| {
+                (
+                    input
+                        .face_buttons()
+                        .iter()
+                        .map(|button| format!("{button:?}"))
+                        .collect(),
+                    input.stick_x(),
+                    input.stick_y(),
+                    format!("{:?}", input.dpad_button()),
+                    input.frame_duration(),
+                )
+            })
+            .collect()
+    }
+
+    /// One `FRAME_DTYPE` record per frame, e.g.
+    /// `numpy.frombuffer(inputs.frame_inputs(), dtype=InputData.FRAME_DTYPE)`
+    fn frame_inputs<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.0.frame_records())
+    }
+}
+
+/// The footer CTGP adds to the ghosts it saves
+#[pyclass(name = "CTGPMetadata", module = "rkg_utils", frozen)]
+pub struct PyCTGPMetadata(crate::ctgp_metadata::CTGPMetadata);
+
+#[pymethods]
+impl PyCTGPMetadata {
+    /// Takes the whole ghost file, the footer is read from its end
+    #[new]
+    fn new(data: &[u8]) -> PyResult<Self> {
+        crate::ctgp_metadata::CTGPMetadata::new(data)
+            .map(Self)
+            .map_err(to_py_err)
+    }
+
+    #[getter]
+    fn player_id(&self) -> u64 {
+        self.0.player_id()
+    }
+
+    #[getter]
+    fn track_sha1<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, self.0.track_sha1())
+    }
+
+    #[getter]
+    fn category(&self) -> String {
+        format!("{:?}", self.0.category())
+    }
+
+    #[getter]
+    fn ctgp_version(&self) -> Option<String> {
+        self.0.ctgp_version().map(|version| version.to_string())
+    }
+
+    #[getter]
+    fn exact_finish_time(&self) -> String {
+        self.0.exact_finish_time().to_string()
+    }
+
+    #[getter]
+    fn exact_lap_times(&self) -> Vec<String> {
+        self.0
+            .exact_lap_times()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[getter]
+    fn pause_frames(&self) -> Vec<u32> {
+        self.0.pause_frames().clone()
+    }
+
+    #[getter]
+    fn respawns(&self) -> bool {
+        self.0.respawns()
+    }
+
+    #[getter]
+    fn went_oob(&self) -> bool {
+        self.0.went_oob()
+    }
+
+    #[getter]
+    fn cannoned(&self) -> bool {
+        self.0.cannoned()
+    }
+
+    #[getter]
+    fn has_slowdown(&self) -> bool {
+        self.0.has_slowdown()
+    }
+
+    #[getter]
+    fn has_rapidfire(&self) -> bool {
+        self.0.has_rapidfire()
+    }
+
+    #[getter]
+    fn dubious_ghost(&self) -> bool {
+        self.0.dubious_ghost()
+    }
+}
+
+/// `buttons` uses the face button bits of the file, `dpad` counts from `None` = 0
+const FRAME_DTYPE: [(&str, &str); 4] = [
+    ("buttons", "u1"),
+    ("stick_x", "i1"),
+    ("stick_y", "i1"),
+    ("dpad", "u1"),
+];
+
+#[pymodule]
+#[pyo3(name = "rkg_utils")]
+pub(crate) fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyGhost>()?;
+    m.add_class::<PyHeader>()?;
+    m.add_class::<PyMii>()?;
+    m.add_class::<PyInputData>()?;
+    m.add_class::<PyCTGPMetadata>()?;
+    m.add("RkgError", m.py().get_type::<RkgError>())?;
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use crate::{
+    byte_handler::FromByteHandler,
+    header::{
+        combo::{Combo, ComboError},
+        controller::{Controller, ControllerError},
+        date::{Date, DateError},
+        in_game_time::{InGameTime, InGameTimeError},
+        mii::{
+            mii_id::{MiiId, MiiIdError},
+            system_id::{SystemId, SystemIdError},
+            utf16be_to_string,
+        },
+        slot_id::{SlotId, SlotIdError},
+    },
+};
+use alloc::string::String;
+
+/// Size of a single RKPD block
+pub const LICENSE_SIZE: usize = 0x8CC0;
+/// Offset of the personal best table inside of a license
+pub(crate) const PERSONAL_BESTS_OFFSET: usize = 0x0DC0;
+/// Size of a single entry in the personal best table
+pub(crate) const PERSONAL_BEST_SIZE: usize = 0x60;
+/// Number of tracks with a personal best (and personal best ghost slot) per license
+pub const TRACK_COUNT: usize = 32;
+
+#[derive(thiserror::Error, Debug)]
+pub enum LicenseError {
+    #[error("License is not RKPD")]
+    NotRKPD,
+    #[error("Data passed is not correct size (0x8CC0)")]
+    NotCorrectSize,
+    #[error("FromUtf16Error: {0}")]
+    FromUtf16Error(#[from] alloc::string::FromUtf16Error),
+    #[error("Mii ID Error: {0}")]
+    MiiIdError(#[from] MiiIdError),
+    #[error("System ID Error: {0}")]
+    SystemIdError(#[from] SystemIdError),
+    #[error("Personal Best Error: {0}")]
+    PersonalBestError(#[from] PersonalBestError),
+}
+
+/// A license (RKPD) of the save file
+/// https://wiki.tockdom.com/wiki/Rksys.dat#License
+pub struct License {
+    mii_name: String,
+    mii_id: MiiId,
+    system_id: SystemId,
+    personal_bests: [Option<PersonalBest>; TRACK_COUNT],
+}
+
+impl License {
+    /// Reads license from slice, returns None if the license slot is unused
+    pub fn new(license_data: &[u8]) -> Result<Option<Self>, LicenseError> {
+        if license_data.len() != LICENSE_SIZE {
+            return Err(LicenseError::NotCorrectSize);
+        }
+        if license_data[0..4] == [0; 4] {
+            return Ok(None);
+        }
+        if license_data[0..4] != [0x52, 0x4B, 0x50, 0x44] {
+            return Err(LicenseError::NotRKPD);
+        }
+
+        // The license only references the Mii in the console's Mii database (RFL_DB.dat)
+        let mii_name = utf16be_to_string(&license_data[0x14..0x28])?;
+        let mii_id = MiiId::from_byte_handler(&license_data[0x28..0x2C])?;
+        let system_id = SystemId::from_byte_handler(&license_data[0x2C..0x30])?;
+
+        let mut personal_bests = [None; TRACK_COUNT];
+        for (index, personal_best) in personal_bests.iter_mut().enumerate() {
+            let start = PERSONAL_BESTS_OFFSET + index * PERSONAL_BEST_SIZE;
+            *personal_best = PersonalBest::new(&license_data[start..start + PERSONAL_BEST_SIZE])?;
+        }
+
+        Ok(Some(Self {
+            mii_name,
+            mii_id,
+            system_id,
+            personal_bests,
+        }))
+    }
+
+    pub fn mii_name(&self) -> &str {
+        &self.mii_name
+    }
+
+    pub fn mii_id(&self) -> MiiId {
+        self.mii_id
+    }
+
+    pub fn system_id(&self) -> SystemId {
+        self.system_id
+    }
+
+    /// Personal bests indexed by track slot ID, None if the track hasn't been raced yet
+    pub fn personal_bests(&self) -> &[Option<PersonalBest>] {
+        &self.personal_bests
+    }
+
+    pub fn personal_best(&self, slot_id: SlotId) -> Option<&PersonalBest> {
+        self.personal_bests
+            .get(u8::from(slot_id) as usize)?
+            .as_ref()
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum PersonalBestError {
+    #[error("Data passed is too short (0x54)")]
+    TooShort,
+    #[error("In Game Time Error: {0}")]
+    InGameTimeError(#[from] InGameTimeError),
+    #[error("Slot ID Error: {0}")]
+    SlotIdError(#[from] SlotIdError),
+    #[error("Combo Error: {0}")]
+    ComboError(#[from] ComboError),
+    #[error("Date Error: {0}")]
+    DateError(#[from] DateError),
+    #[error("Controller Error: {0}")]
+    ControllerError(#[from] ControllerError),
+}
+
+/// Entry of a license's personal best table. Bytes 0x4C..0x54 use the same packing as RKG header 0x04..0x0C
+/// 1. 0x00..0x4A Mii data
+/// 2. 0x4A..0x4C Mii CRC16
+/// 3. 0x4C..0x4F Finish time
+/// 4. 0x4F Slot ID
+/// 5. 0x50..0x54 Combo, date set and controller
+#[derive(Clone, Copy)]
+pub struct PersonalBest {
+    finish_time: InGameTime,
+    slot_id: SlotId,
+    combo: Combo,
+    date_set: Date,
+    controller: Controller,
+}
+
+impl PersonalBest {
+    /// Reads a personal best table entry, returns None if the entry is empty
+    pub fn new(entry: &[u8]) -> Result<Option<Self>, PersonalBestError> {
+        if entry.len() < 0x54 {
+            return Err(PersonalBestError::TooShort);
+        }
+        if entry[0x4C..0x4F] == [0; 3] {
+            return Ok(None);
+        }
+
+        Ok(Some(Self {
+            finish_time: InGameTime::from_byte_handler(&entry[0x4C..0x4F])?,
+            slot_id: SlotId::from_byte_handler(entry[0x4F])?,
+            combo: Combo::from_byte_handler(&entry[0x50..0x52])?,
+            date_set: Date::from_byte_handler(&entry[0x51..0x54])?,
+            controller: Controller::from_byte_handler(entry[0x53])?,
+        }))
+    }
+
+    pub fn finish_time(&self) -> &InGameTime {
+        &self.finish_time
+    }
+
+    pub fn slot_id(&self) -> SlotId {
+        self.slot_id
+    }
+
+    pub fn combo(&self) -> &Combo {
+        &self.combo
+    }
+
+    pub fn date_set(&self) -> &Date {
+        &self.date_set
+    }
+
+    pub fn controller(&self) -> Controller {
+        self.controller
+    }
+}
@@ -0,0 +1,414 @@
+use crate::{
+    crc::crc32,
+    header::{Header, HeaderError},
+    input_data::{InputData, InputDataError, yaz1_compress},
+    save::license::{
+        License, LicenseError, PERSONAL_BEST_SIZE, PERSONAL_BESTS_OFFSET, TRACK_COUNT,
+    },
+};
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use std::io::{Read, Write};
+
+pub mod license;
+
+/// Size of a Mario Kart Wii save file
+pub const SAVE_SIZE: usize = 0x2BC000;
+/// Offset of the first license, the 4 licenses follow each other
+pub(crate) const LICENSES_OFFSET: usize = 0x08;
+/// Offset of the CRC32 of everything before it (the header and licenses)
+pub(crate) const CHECKSUM_OFFSET: usize = 0x27FFC;
+/// Offset of the ghost data, the 4 license ghost blocks follow each other
+pub(crate) const GHOSTS_OFFSET: usize = 0x28000;
+/// Size of the ghost block of a single license
+pub(crate) const LICENSE_GHOSTS_SIZE: usize = 0xA5000;
+/// Size of a single ghost slot, the last 4 bytes are the CRC32 of the rest of the slot
+pub const GHOST_SLOT_SIZE: usize = 0x2800;
+/// Number of downloaded ghost slots per license
+pub const DOWNLOADED_GHOST_COUNT: usize = 32;
+/// Number of licenses in the save file
+pub const LICENSE_COUNT: usize = 4;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SaveFileError {
+    #[error("File is not RKSD")]
+    NotRKSD,
+    #[error("Data passed is not correct size (0x2BC000)")]
+    NotCorrectSize,
+    #[error("Ghost doesn't fit in a slot")]
+    GhostTooLong,
+    #[error("Ghost ends before its input data")]
+    GhostTooShort,
+    #[error("License doesn't exist")]
+    NonexistentLicense,
+    #[error("Slot index is out of range")]
+    InvalidSlotIndex,
+    #[error("License Error: {0}")]
+    LicenseError(#[from] LicenseError),
+    #[error("Header Error: {0}")]
+    HeaderError(#[from] HeaderError),
+    #[error("Input Data Error: {0}")]
+    InputDataError(#[from] InputDataError),
+    #[cfg(feature = "std")]
+    #[error("Io Error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+/// Which kind of slot of a license a ghost is saved in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GhostSlotKind {
+    /// Indexed by track slot ID
+    PersonalBest,
+    Downloaded,
+}
+
+/// A ghost embedded in the save file
+pub struct SaveGhost {
+    license_index: usize,
+    slot_kind: GhostSlotKind,
+    slot_index: usize,
+    header: Header,
+    input_data: InputData,
+}
+
+impl SaveGhost {
+    pub fn license_index(&self) -> usize {
+        self.license_index
+    }
+
+    pub fn slot_kind(&self) -> GhostSlotKind {
+        self.slot_kind
+    }
+
+    pub fn slot_index(&self) -> usize {
+        self.slot_index
+    }
+
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    pub fn input_data(&self) -> &InputData {
+        &self.input_data
+    }
+}
+
+/// A ghost slot that starts like an RKG but couldn't be read
+#[derive(Debug)]
+pub struct InvalidGhostSlot {
+    license_index: usize,
+    slot_kind: GhostSlotKind,
+    slot_index: usize,
+    error: SaveFileError,
+}
+
+impl InvalidGhostSlot {
+    pub fn license_index(&self) -> usize {
+        self.license_index
+    }
+
+    pub fn slot_kind(&self) -> GhostSlotKind {
+        self.slot_kind
+    }
+
+    pub fn slot_index(&self) -> usize {
+        self.slot_index
+    }
+
+    pub fn error(&self) -> &SaveFileError {
+        &self.error
+    }
+}
+
+/// Mario Kart Wii save file (rksys.dat)
+/// https://wiki.tockdom.com/wiki/Rksys.dat
+pub struct SaveFile {
+    licenses: [Option<License>; LICENSE_COUNT],
+    ghosts: Vec<SaveGhost>,
+    invalid_ghost_slots: Vec<InvalidGhostSlot>,
+    bytes: Vec<u8>,
+}
+
+impl SaveFile {
+    /// Reads save file from a file at the path
+    #[cfg(feature = "std")]
+    pub fn new_from_path<P: AsRef<std::path::Path>>(p: P) -> Result<Self, SaveFileError> {
+        let mut save_data = Vec::with_capacity(SAVE_SIZE);
+        std::fs::File::open(p)?.read_to_end(&mut save_data)?;
+        Self::new(&save_data)
+    }
+
+    /// Reads save file from slice. Ghost slots that can't be read don't fail the whole save,
+    /// they're listed in `invalid_ghost_slots` instead.
+    pub fn new(save_data: &[u8]) -> Result<Self, SaveFileError> {
+        if save_data.len() != SAVE_SIZE {
+            return Err(SaveFileError::NotCorrectSize);
+        }
+        if save_data[0..4] != [0x52, 0x4B, 0x53, 0x44] {
+            return Err(SaveFileError::NotRKSD);
+        }
+
+        let mut licenses = [None, None, None, None];
+        let mut ghosts = Vec::new();
+        let mut invalid_ghost_slots = Vec::new();
+
+        for (license_index, license) in licenses.iter_mut().enumerate() {
+            let start = license::LICENSE_SIZE * license_index + LICENSES_OFFSET;
+            *license = License::new(&save_data[start..start + license::LICENSE_SIZE])?;
+
+            for (slot_kind, slot_index) in slots() {
+                let slot = ghost_slot(save_data, license_index, slot_kind, slot_index);
+
+                match read_ghost_slot(slot) {
+                    Ok(Some((header, input_data))) => ghosts.push(SaveGhost {
+                        license_index,
+                        slot_kind,
+                        slot_index,
+                        header,
+                        input_data,
+                    }),
+                    Ok(None) => {}
+                    Err(error) => invalid_ghost_slots.push(InvalidGhostSlot {
+                        license_index,
+                        slot_kind,
+                        slot_index,
+                        error,
+                    }),
+                }
+            }
+        }
+
+        Ok(Self {
+            licenses,
+            ghosts,
+            invalid_ghost_slots,
+            bytes: Vec::from(save_data),
+        })
+    }
+
+    /// Writes save file to a file at the path
+    #[cfg(feature = "std")]
+    pub fn save_to_path<P: AsRef<std::path::Path>>(&self, p: P) -> Result<(), SaveFileError> {
+        std::fs::File::create(p)?.write_all(&self.bytes)?;
+        Ok(())
+    }
+
+    /// Raw save file, including every modification made so far
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns true if the CRC32 of the header and licenses is correct
+    pub fn verify_checksum(&self) -> bool {
+        crc32(&self.bytes[..CHECKSUM_OFFSET]).to_be_bytes()
+            == self.bytes[CHECKSUM_OFFSET..CHECKSUM_OFFSET + 0x04]
+    }
+
+    /// Recalculates and updates the CRC32 of the header and licenses
+    pub fn fix_checksum(&mut self) {
+        write_checksum(&mut self.bytes);
+    }
+
+    /// Installs an RKG as the personal best ghost of its track and updates the license's personal best time.
+    /// CTGP metadata is dropped and the input data is YAZ1 compressed if the ghost wouldn't fit otherwise.
+    pub fn install_personal_best_ghost(
+        &mut self,
+        license_index: usize,
+        rkg_data: &[u8],
+    ) -> Result<(), SaveFileError> {
+        let header = Header::new(rkg_data.get(..0x88).ok_or(HeaderError::NotCorrectSize)?)?;
+        let slot_index = u8::from(header.slot_id()) as usize;
+        if slot_index >= TRACK_COUNT {
+            return Err(SaveFileError::InvalidSlotIndex);
+        }
+
+        let license_offset = self.license_offset(license_index)?;
+        let ghost = slot_ghost(rkg_data)?;
+
+        let mut save_data = self.bytes.clone();
+        let personal_best =
+            license_offset + PERSONAL_BESTS_OFFSET + slot_index * PERSONAL_BEST_SIZE;
+        // Mii data and CRC16, then finish time, slot ID, combo, date set and controller
+        save_data[personal_best..personal_best + 0x4C].copy_from_slice(&rkg_data[0x3C..0x88]);
+        save_data[personal_best + 0x4C..personal_best + 0x54]
+            .copy_from_slice(&rkg_data[0x04..0x0C]);
+
+        self.install_ghost(
+            save_data,
+            license_index,
+            GhostSlotKind::PersonalBest,
+            slot_index,
+            &ghost,
+        )
+    }
+
+    /// Installs an RKG into one of the downloaded ghost slots of a license
+    pub fn install_downloaded_ghost(
+        &mut self,
+        license_index: usize,
+        slot_index: usize,
+        rkg_data: &[u8],
+    ) -> Result<(), SaveFileError> {
+        if slot_index >= DOWNLOADED_GHOST_COUNT {
+            return Err(SaveFileError::InvalidSlotIndex);
+        }
+        self.license_offset(license_index)?;
+        let ghost = slot_ghost(rkg_data)?;
+
+        self.install_ghost(
+            self.bytes.clone(),
+            license_index,
+            GhostSlotKind::Downloaded,
+            slot_index,
+            &ghost,
+        )
+    }
+
+    /// Writes the ghost into `save_data`, a copy of the save, and only replaces `self` once it parses
+    fn install_ghost(
+        &mut self,
+        mut save_data: Vec<u8>,
+        license_index: usize,
+        slot_kind: GhostSlotKind,
+        slot_index: usize,
+        ghost: &[u8],
+    ) -> Result<(), SaveFileError> {
+        let start = ghost_slot_offset(license_index, slot_kind, slot_index);
+        let slot = &mut save_data[start..start + GHOST_SLOT_SIZE];
+        slot.fill(0);
+        slot[..ghost.len()].copy_from_slice(ghost);
+        let checksum = crc32(&slot[..GHOST_SLOT_SIZE - 0x04]);
+        slot[GHOST_SLOT_SIZE - 0x04..].copy_from_slice(&checksum.to_be_bytes());
+
+        write_checksum(&mut save_data);
+
+        // Parse everything again so licenses and ghosts reflect the new bytes. Unreadable slots
+        // elsewhere only end up in `invalid_ghost_slots`, so they can't fail the install.
+        *self = Self::new(&save_data)?;
+        Ok(())
+    }
+
+    fn license_offset(&self, license_index: usize) -> Result<usize, SaveFileError> {
+        if self.license(license_index).is_none() {
+            return Err(SaveFileError::NonexistentLicense);
+        }
+
+        Ok(license::LICENSE_SIZE * license_index + LICENSES_OFFSET)
+    }
+
+    pub fn licenses(&self) -> &[Option<License>] {
+        &self.licenses
+    }
+
+    pub fn license(&self, index: usize) -> Option<&License> {
+        self.licenses.get(index)?.as_ref()
+    }
+
+    /// Every ghost in the save file, in license and slot order
+    pub fn ghosts(&self) -> &[SaveGhost] {
+        &self.ghosts
+    }
+
+    /// Ghost slots holding an RKG that couldn't be read, in license and slot order
+    pub fn invalid_ghost_slots(&self) -> &[InvalidGhostSlot] {
+        &self.invalid_ghost_slots
+    }
+}
+
+/// Writes the CRC32 of everything before it at the end of the save data
+fn write_checksum(save_data: &mut [u8]) {
+    let checksum = crc32(&save_data[..CHECKSUM_OFFSET]);
+    save_data[CHECKSUM_OFFSET..CHECKSUM_OFFSET + 0x04].copy_from_slice(&checksum.to_be_bytes());
+}
+
+/// Every ghost slot of a license, personal bests first
+fn slots() -> impl Iterator<Item = (GhostSlotKind, usize)> {
+    (0..TRACK_COUNT)
+        .map(|index| (GhostSlotKind::PersonalBest, index))
+        .chain((0..DOWNLOADED_GHOST_COUNT).map(|index| (GhostSlotKind::Downloaded, index)))
+}
+
+/// Offset of a ghost slot in the save file
+pub(crate) fn ghost_slot_offset(
+    license_index: usize,
+    slot_kind: GhostSlotKind,
+    slot_index: usize,
+) -> usize {
+    let slot_offset = match slot_kind {
+        GhostSlotKind::PersonalBest => slot_index,
+        GhostSlotKind::Downloaded => TRACK_COUNT + slot_index,
+    } * GHOST_SLOT_SIZE;
+
+    GHOSTS_OFFSET + license_index * LICENSE_GHOSTS_SIZE + slot_offset
+}
+
+fn ghost_slot(
+    save_data: &[u8],
+    license_index: usize,
+    slot_kind: GhostSlotKind,
+    slot_index: usize,
+) -> &[u8] {
+    let start = ghost_slot_offset(license_index, slot_kind, slot_index);
+    &save_data[start..start + GHOST_SLOT_SIZE]
+}
+
+/// Parses the RKG in a ghost slot, returns None if the slot is empty
+fn read_ghost_slot(slot: &[u8]) -> Result<Option<(Header, InputData)>, SaveFileError> {
+    if slot[0..4] != [0x52, 0x4B, 0x47, 0x44] {
+        return Ok(None);
+    }
+
+    let header = Header::new(&slot[..0x88])?;
+    let input_data_length = input_data_length(&header, slot)?;
+
+    if 0x88 + input_data_length > GHOST_SLOT_SIZE - 0x04 {
+        return Err(SaveFileError::GhostTooLong);
+    }
+
+    let input_data = InputData::new(&slot[0x88..0x88 + input_data_length])?;
+
+    Ok(Some((header, input_data)))
+}
+
+/// Strips everything after the input data of an RKG (CRC32, CTGP metadata) and compresses the
+/// input data if the ghost wouldn't fit in a slot otherwise
+fn slot_ghost(rkg_data: &[u8]) -> Result<Vec<u8>, SaveFileError> {
+    let header = Header::new(rkg_data.get(..0x88).ok_or(HeaderError::NotCorrectSize)?)?;
+    let input_data_length = input_data_length(&header, rkg_data)?;
+
+    let input_data = rkg_data
+        .get(0x88..0x88 + input_data_length)
+        .ok_or(SaveFileError::GhostTooLong)?;
+    // Make sure the input data is valid before writing it to the save
+    InputData::new(input_data)?;
+
+    let mut ghost = Vec::from(&rkg_data[..0x88]);
+    if header.is_compressed() || 0x88 + input_data.len() <= GHOST_SLOT_SIZE - 0x04 {
+        ghost.extend_from_slice(input_data);
+    } else {
+        let compressed = yaz1_compress(input_data);
+        ghost[0x0C] |= 0x08; // Compressed flag
+        ghost.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+        ghost.extend_from_slice(&compressed);
+    }
+
+    if ghost.len() > GHOST_SLOT_SIZE - 0x04 {
+        return Err(SaveFileError::GhostTooLong);
+    }
+
+    Ok(ghost)
+}
+
+/// Length of the input data following the header, including the compressed size if compressed
+fn input_data_length(header: &Header, rkg_data: &[u8]) -> Result<usize, SaveFileError> {
+    if !header.is_compressed() {
+        return Ok(header.decompressed_input_data_length() as usize);
+    }
+
+    // Compressed size, then the YAZ1 data itself
+    let compressed_size = rkg_data
+        .get(0x88..0x8C)
+        .ok_or(SaveFileError::GhostTooShort)?;
+    Ok(u32::from_be_bytes(compressed_size.try_into().unwrap()) as usize + 0x04)
+}
@@ -1,18 +1,31 @@
+use std::{prelude::rust_2024::*, print, println, vec};
+
 use crate::{
-    ctgp_metadata::CTGPMetadata,
+    Ghost,
+    audit::{Severity, Verdict, audit},
+    byte_handler::{ByteHandler, FieldCodec, FromByteHandler},
+    crc::crc32,
+    ctgp_metadata::{CTGPMetadata, exact_finish_time::ExactFinishTime},
     header::{
-        Header,
-        combo::{Character, Vehicle},
+        Header, HeaderError,
+        combo::{
+            Character, Combo, ComboError, ComboIssue, ComboValidation, GetWeightClass, Vehicle,
+            WeightClass,
+        },
         controller::Controller,
         date::Date,
-        ghost_type::GhostType,
+        ghost_type::{GhostType, GhostTypeError},
+        in_game_time::{InGameTime, InGameTimeError},
         location::country::Country,
         mii::{
+            Mii,
+            audit::{MiiAuditIssue, MiiNameField},
             eyebrows::EyebrowType,
             eyes::{EyeColor, EyeType},
             facial_hair::{BeardType, MustacheType},
             fav_color::FavColor,
             glasses::{GlassesColor, GlassesType},
+            glyphs,
             hair::{HairColor, HairType},
             head::{FaceFeatures, HeadShape, SkinTone},
             lips::{LipsColor, LipsType},
@@ -20,7 +33,18 @@ use crate::{
         },
         slot_id::SlotId,
     },
-    input_data::InputData,
+    input_data::{
+        InputData,
+        face_input::{FaceButton, face_buttons_to_byte, parse_face_buttons},
+        lap_inputs::LapSegment,
+        yaz1_compress, yaz1_decompress,
+    },
+    integrity::{IntegrityIssue, verify_integrity},
+    save::{
+        GhostSlotKind, SaveFile, SaveFileError,
+        license::{PersonalBest, PersonalBestError},
+    },
+    timing::{TimingIssue, TimingReport, race_clock::RaceClock},
 };
 use std::io::Read;
 
@@ -48,8 +72,15 @@ fn test_rkg_header() {
     assert_eq!(header.lap_split_times()[1].to_string(), "00:19.127");
     assert_eq!(header.lap_split_times()[2].to_string(), "00:19.237");
     assert_eq!(header.country(), Country::NotSet);
+    assert_eq!(header.country_id(), 0xFF);
     assert_eq!(header.subregion(), 0xFF);
     assert_eq!(header.location_code(), 0xFFFF);
+    let david_header = Header::new_from_path("./test_ghosts/00m58s6479888 David .rkg")
+        .expect("Couldn't read header");
+    assert_eq!(david_header.country(), Country::UnitedKingdom);
+    assert_eq!(Country::try_from(0x80).unwrap(), Country::Taiwan);
+    // Extended Regions ID, only the raw value is kept
+    assert!(Country::try_from(0x02).is_err());
 
     // Mii Data
     assert!(!header.mii().is_girl());
@@ -60,8 +91,8 @@ fn test_rkg_header() {
     assert_eq!(header.mii().build().height(), 127);
     assert_eq!(header.mii().build().weight(), 127);
 
-    assert_eq!(header.mii().mii_id(), 0x893EF2FB);
-    assert_eq!(header.mii().system_id(), 0x689EC992);
+    assert_eq!(header.mii().mii_id().raw(), 0x893EF2FB);
+    assert_eq!(header.mii().system_id().raw(), 0x689EC992);
 
     assert_eq!(header.mii().head().shape(), HeadShape::Large);
     assert_eq!(header.mii().head().skin_tone(), SkinTone::Natural);
@@ -315,10 +346,10 @@ fn test_nine_laps() {
     let header = Header::new(&rkg_data[..0x88]).expect("Couldn't read header");
 
     for (index, lap) in header.lap_split_times().iter().enumerate() {
-        println!("Lap {}: {}", index + 1, lap.to_string());
+        println!("Lap {}: {}", index + 1, lap);
     }
 
-    println!("\nTotal time: {}", header.finish_time().to_string());
+    println!("\nTotal time: {}", header.finish_time());
 }
 
 #[test]
@@ -348,3 +379,1842 @@ fn test_exact_finish_time() {
         "00:19.417404176835"
     );
 }
+
+#[test]
+fn test_mii_id_and_system_id() {
+    let header =
+        Header::new_from_path("./test_ghosts/JC_LC_Compressed.rkg").expect("Couldn't read header");
+
+    let mii_id = header.mii().mii_id();
+    assert!(mii_id.is_normal());
+    assert!(!mii_id.is_special());
+    assert!(!mii_id.is_foreign());
+    assert_eq!(mii_id.created_at().to_string(), "2025-08-30 11:58:36");
+
+    let system_id = header.mii().system_id();
+    assert_eq!(system_id.mac_checksum(), 0x68);
+    assert_eq!(system_id.mac_suffix(), [0x9E, 0xC9, 0x92]);
+    assert!(system_id.matches_mac_address([0x00, 0x22, 0x46, 0x9E, 0xC9, 0x92]));
+    assert!(!system_id.matches_mac_address([0x00, 0x22, 0x47, 0x9E, 0xC9, 0x92]));
+}
+
+#[test]
+fn test_mii_audit() {
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/JC_LC_Compressed.rkg")
+        .expect("Couldn't find `./test_ghosts/JC_LC_Compressed.rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+
+    let mut mii_data = [0u8; 0x4A];
+    mii_data.copy_from_slice(&rkg_data[0x3C..0x3C + 0x4A]);
+    assert!(Mii::new(mii_data).unwrap().audit().is_empty());
+
+    // No birthday month but day 5, eyebrow rotation 31 and a control character in the name
+    mii_data[0x00] &= 0xC0;
+    mii_data[0x01] = (mii_data[0x01] & 0x1F) | (5 << 5);
+    mii_data[0x24] |= 0x07;
+    mii_data[0x25] |= 0xC0;
+    mii_data[0x05] = 0x07;
+
+    let issues = Mii::new(mii_data).unwrap().audit();
+    assert_eq!(
+        issues,
+        [
+            MiiAuditIssue::BirthdayDayWithoutMonth { day: 5 },
+            MiiAuditIssue::InvalidNameCharacter {
+                field: MiiNameField::Name,
+                code_unit: 0x0007
+            },
+            MiiAuditIssue::OutOfRange {
+                field: "eyebrows.rotation",
+                value: 31,
+                min: 0,
+                max: 11
+            },
+        ]
+    );
+
+    // Keyboard symbols and Wii glyphs are fine, other symbols and private use characters aren't
+    mii_data.copy_from_slice(&rkg_data[0x3C..0x3C + 0x4A]);
+    mii_data[0x02..0x0C].copy_from_slice(&[0x26, 0x06, 0xE0, 0x17, 0x26, 0x00, 0xE1, 0x00, 0, 0]);
+    let issues = Mii::new(mii_data).unwrap().audit();
+    assert_eq!(
+        issues,
+        [0x2600, 0xE100].map(|code_unit| MiiAuditIssue::InvalidNameCharacter {
+            field: MiiNameField::Name,
+            code_unit
+        })
+    );
+
+    // Downloaded and marked as favorite
+    mii_data.copy_from_slice(&rkg_data[0x3C..0x3C + 0x4A]);
+    mii_data[0x01] &= 0xFE;
+    mii_data[0x21] |= 0x01;
+    let mii = Mii::new(mii_data).unwrap();
+    assert!(mii.is_favorite() && mii.downloaded());
+    assert!(mii.audit().contains(&MiiAuditIssue::DownloadedFavorite));
+}
+
+#[test]
+fn test_mii_display_names() {
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/01m44s4420944 chϊcκεη.rkg")
+        .expect("Couldn't find `./test_ghosts/01m44s4420944 chϊcκεη.rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+
+    let header = Header::new(&rkg_data[..0x88]).expect("Couldn't read header");
+    assert_eq!(header.mii().display_name(), "chϊcκεη");
+    assert_eq!(header.mii().filename_safe_name(), "chϊcκεη");
+
+    // "A♥/?." using the Wii's heart glyph
+    let mut mii_data = [0u8; 0x4A];
+    mii_data.copy_from_slice(&rkg_data[0x3C..0x3C + 0x4A]);
+    mii_data[0x02..0x16].fill(0);
+    mii_data[0x02..0x0C]
+        .copy_from_slice(&[0x00, 0x41, 0xE0, 0x17, 0x00, 0x2F, 0x00, 0x3F, 0x00, 0x2E]);
+
+    let mii = Mii::new(mii_data).unwrap();
+    assert_eq!(mii.name(), "A\u{E017}/?.");
+    assert_eq!(mii.display_name(), "A♥/?.");
+    assert_eq!(mii.filename_safe_name(), "A♥__");
+
+    assert_eq!(glyphs::to_filename_safe_string("A\u{0}B\u{1F}"), "A_B_");
+    assert_eq!(glyphs::to_filename_safe_string("con"), "con_");
+    assert_eq!(glyphs::to_filename_safe_string("LPT1.rkg"), "LPT1_.rkg");
+    assert_eq!(glyphs::to_filename_safe_string("COM0"), "COM0");
+    assert_eq!(glyphs::to_filename_safe_string("Connor"), "Connor");
+}
+
+/// Builds a save with JC's ghost as license 1's Luigi Circuit personal best and as its first downloaded ghost
+fn build_test_save() -> Vec<u8> {
+    let mut uncompressed: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/JC_LC.rkg")
+        .expect("Couldn't find `./test_ghosts/JC_LC.rkg`")
+        .read_to_end(&mut uncompressed)
+        .expect("Couldn't read bytes in file");
+
+    let mut compressed: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/JC_LC_Compressed.rkg")
+        .expect("Couldn't find `./test_ghosts/JC_LC_Compressed.rkg`")
+        .read_to_end(&mut compressed)
+        .expect("Couldn't read bytes in file");
+    // Strip the CTGP footer, zeroed CRC32 instead
+    compressed.truncate(compressed.len() - 0xE0);
+    compressed.extend_from_slice(&[0; 4]);
+
+    let mut save = vec![0u8; 0x2BC000];
+    save[0x00..0x08].copy_from_slice(b"RKSD0006");
+
+    let license = 0x08;
+    save[license..license + 4].copy_from_slice(b"RKPD");
+    save[license + 0x14..license + 0x18].copy_from_slice(&[0x00, 0x4A, 0x00, 0x43]);
+    save[license + 0x28..license + 0x30].copy_from_slice(&uncompressed[0x3C + 0x18..0x3C + 0x20]);
+    let personal_best = license + 0x0DC0 + 0x08 * 0x60;
+    save[personal_best..personal_best + 0x4E].copy_from_slice(&uncompressed[0x3C..0x3C + 0x4E]);
+    save[personal_best + 0x4C..personal_best + 0x54].copy_from_slice(&uncompressed[0x04..0x0C]);
+
+    let personal_best_slot = 0x28000 + 0x08 * 0x2800;
+    save[personal_best_slot..personal_best_slot + uncompressed.len()]
+        .copy_from_slice(&uncompressed);
+    let downloaded_slot = 0x28000 + 32 * 0x2800;
+    save[downloaded_slot..downloaded_slot + compressed.len()].copy_from_slice(&compressed);
+
+    save
+}
+
+#[test]
+fn test_read_save_file() {
+    let save = SaveFile::new(&build_test_save()).expect("Couldn't read save file");
+
+    let license = save.license(0).expect("License 1 should exist");
+    assert!(save.license(1).is_none());
+    assert_eq!(license.mii_name(), "JC");
+    assert_eq!(license.mii_id().raw(), 0x893EF2FB);
+    assert_eq!(license.system_id().raw(), 0x689EC992);
+
+    let personal_best = license
+        .personal_best(SlotId::LuigiCircuit)
+        .expect("Luigi Circuit personal best should exist");
+    assert_eq!(personal_best.finish_time().to_string(), "01:03.904");
+    assert_eq!(personal_best.combo().character(), Character::KingBoo);
+    assert_eq!(personal_best.controller(), Controller::Classic);
+    assert!(license.personal_best(SlotId::MarioCircuit).is_none());
+    assert!(matches!(
+        PersonalBest::new(&[0; 0x4F]),
+        Err(PersonalBestError::TooShort)
+    ));
+
+    assert_eq!(save.ghosts().len(), 2);
+    let personal_best_ghost = &save.ghosts()[0];
+    assert_eq!(personal_best_ghost.slot_kind(), GhostSlotKind::PersonalBest);
+    assert_eq!(personal_best_ghost.slot_index(), 0x08);
+    assert!(!personal_best_ghost.header().is_compressed());
+
+    let downloaded_ghost = &save.ghosts()[1];
+    assert_eq!(downloaded_ghost.slot_kind(), GhostSlotKind::Downloaded);
+    assert!(downloaded_ghost.header().is_compressed());
+    assert_eq!(
+        personal_best_ghost.input_data().inputs(),
+        downloaded_ghost.input_data().inputs()
+    );
+    assert!(save.invalid_ghost_slots().is_empty());
+
+    // A broken slot is reported on its own, the rest of the save still loads and can be edited
+    let mut save_data = build_test_save();
+    let broken_slot = 0x28000 + (32 + 3) * 0x2800;
+    save_data[broken_slot..broken_slot + 4].copy_from_slice(b"RKGD");
+    let mut save = SaveFile::new(&save_data).expect("Couldn't read save file");
+    assert_eq!(save.ghosts().len(), 2);
+    assert_eq!(save.invalid_ghost_slots().len(), 1);
+    let invalid_slot = &save.invalid_ghost_slots()[0];
+    assert_eq!(invalid_slot.slot_kind(), GhostSlotKind::Downloaded);
+    assert_eq!(invalid_slot.slot_index(), 3);
+    assert!(matches!(
+        invalid_slot.error(),
+        SaveFileError::HeaderError(_)
+    ));
+
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/JC_LC.rkg")
+        .expect("Couldn't find `./test_ghosts/JC_LC.rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+    save.install_downloaded_ghost(0, 4, &rkg_data)
+        .expect("Couldn't install downloaded ghost");
+    assert_eq!(save.ghosts().len(), 3);
+    assert_eq!(save.invalid_ghost_slots().len(), 1);
+}
+
+#[test]
+fn test_write_save_file() {
+    let mut save = SaveFile::new(&build_test_save()).expect("Couldn't read save file");
+    assert!(!save.verify_checksum());
+
+    // Dumped straight out of a save file, so installing it must reproduce the slot exactly
+    let mut slot_dump: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/skylar_pause_ghost_vanilla.rkg")
+        .expect("Couldn't find `./test_ghosts/skylar_pause_ghost_vanilla.rkg`")
+        .read_to_end(&mut slot_dump)
+        .expect("Couldn't read bytes in file");
+
+    save.install_downloaded_ghost(0, 5, &slot_dump)
+        .expect("Couldn't install downloaded ghost");
+    let slot = 0x28000 + (32 + 5) * 0x2800;
+    assert_eq!(&save.bytes()[slot..slot + 0x2800], slot_dump.as_slice());
+    assert!(save.verify_checksum());
+
+    // CTGP ghost, the footer has to be dropped
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/00m58s6479888 David .rkg")
+        .expect("Couldn't find `./test_ghosts/00m58s6479888 David .rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+    let header = Header::new(&rkg_data[..0x88]).expect("Couldn't read header");
+
+    save.install_personal_best_ghost(0, &rkg_data)
+        .expect("Couldn't install personal best ghost");
+    assert!(save.verify_checksum());
+
+    let save = SaveFile::new(save.bytes()).expect("Couldn't read modified save file");
+    let personal_best = save
+        .license(0)
+        .unwrap()
+        .personal_best(header.slot_id())
+        .expect("Personal best should have been updated");
+    assert_eq!(
+        personal_best.finish_time().to_string(),
+        header.finish_time().to_string()
+    );
+
+    let ghost = save
+        .ghosts()
+        .iter()
+        .find(|ghost| {
+            ghost.slot_kind() == GhostSlotKind::PersonalBest
+                && ghost.slot_index() == u8::from(header.slot_id()) as usize
+        })
+        .expect("Personal best ghost should have been installed");
+    assert_eq!(ghost.header().mii().name(), "David ");
+
+    // Compressed flag set, but the data ends right after the header
+    let mut header_only = rkg_data[..0x88].to_vec();
+    header_only[0x0C] |= 0x08;
+    let mut save = save;
+    assert!(matches!(
+        save.install_downloaded_ghost(0, 0, &header_only),
+        Err(SaveFileError::GhostTooShort)
+    ));
+}
+
+#[test]
+fn test_yaz1_round_trip() {
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/JC_LC_Compressed.rkg")
+        .expect("Couldn't find `./test_ghosts/JC_LC_Compressed.rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+
+    let decompressed = yaz1_decompress(&rkg_data[0x8C..]).expect("Couldn't decompress");
+    let compressed = yaz1_compress(&decompressed);
+    assert!(compressed.len() < decompressed.len());
+    assert_eq!(yaz1_decompress(&compressed), Some(decompressed));
+}
+
+#[test]
+fn test_in_game_time_arithmetic() {
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/JC_LC.rkg")
+        .expect("Couldn't find `./test_ghosts/JC_LC.rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+
+    let header = Header::new(&rkg_data[..0x88]).expect("Couldn't read header");
+    let finish_time = *header.finish_time();
+    assert_eq!(finish_time.to_bytes().unwrap(), rkg_data[0x04..0x07]);
+
+    let lap_sum: InGameTime = header.lap_split_times().iter().sum();
+    assert_eq!(lap_sum, finish_time);
+    assert!(
+        header
+            .lap_split_times()
+            .iter()
+            .all(|lap| *lap < finish_time)
+    );
+    assert_eq!(
+        lap_sum - header.lap_split_times()[0],
+        header.lap_split_times()[1] + header.lap_split_times()[2]
+    );
+
+    let parsed: InGameTime = "1:03.904".parse().unwrap();
+    assert_eq!(parsed, "01:03.904".parse().unwrap());
+    assert_eq!(parsed, InGameTime::new(1, 3, 904));
+    assert_eq!(parsed.to_string().parse::<InGameTime>().unwrap(), parsed);
+    assert!("1:3.904".parse::<InGameTime>().is_err());
+    assert!(matches!(
+        "1:60.000".parse::<InGameTime>(),
+        Err(InGameTimeError::SecondsInvalid)
+    ));
+
+    let duration = std::time::Duration::from(parsed);
+    assert_eq!(duration.as_millis(), 63904);
+    assert_eq!(
+        InGameTime::try_from(duration + std::time::Duration::from_micros(999)).unwrap(),
+        parsed
+    );
+
+    assert!(InGameTime::try_new(0, 60, 0).is_err());
+    assert!(InGameTime::try_new(0, 0, 1000).is_err());
+    assert!(!InGameTime::new(0, 60, 0).is_technically_valid());
+    assert!(parsed.is_technically_valid());
+    assert_eq!(InGameTime::new(0, 1, 0).checked_sub(parsed), None);
+
+    // The saturating methods never panic, even on unnormalized times
+    let unnormalized = InGameTime::new(127, 127, 1023);
+    assert_eq!(
+        unnormalized.abs_diff(InGameTime::default()),
+        InGameTime::MAX
+    );
+    assert_eq!(unnormalized.saturating_add(unnormalized), InGameTime::MAX);
+    assert_eq!(unnormalized.checked_add(parsed), None);
+    assert_eq!(
+        InGameTime::new(0, 1, 0).saturating_sub(parsed),
+        InGameTime::default()
+    );
+    assert_eq!(
+        unnormalized.saturating_sub(InGameTime::new(0, 0, 1)),
+        InGameTime::MAX
+    );
+    assert_eq!(
+        parsed.abs_diff(InGameTime::new(0, 1, 0)),
+        parsed - InGameTime::new(0, 1, 0)
+    );
+}
+
+#[test]
+#[should_panic(expected = "attempt to add with overflow")]
+fn test_in_game_time_add_overflow() {
+    let _ = [InGameTime::MAX, InGameTime::new(0, 0, 1)]
+        .iter()
+        .sum::<InGameTime>();
+}
+
+#[test]
+#[should_panic(expected = "attempt to subtract with overflow")]
+fn test_in_game_time_sub_overflow() {
+    let _ = InGameTime::new(0, 1, 0) - InGameTime::new(1, 3, 904);
+}
+
+#[test]
+fn test_exact_finish_time_arithmetic() {
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/00m58s6479888 David .rkg")
+        .expect("Couldn't find `./test_ghosts/00m58s6479888 David .rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+
+    let header = Header::new(&rkg_data[..0x88]).expect("Couldn't read header");
+    let ctgp_metadata = CTGPMetadata::new(&rkg_data).expect("Failed to read CTGP metadata");
+    let exact_finish_time = ctgp_metadata.exact_finish_time();
+
+    let lap_sum: ExactFinishTime = ctgp_metadata.exact_lap_times().iter().sum();
+    assert_eq!(lap_sum, exact_finish_time);
+    assert_eq!(exact_finish_time.total_nanoseconds(), 58_647_988_872);
+    assert_eq!(
+        exact_finish_time.to_in_game_time().unwrap(),
+        *header.finish_time()
+    );
+
+    let mut laps = ctgp_metadata.exact_lap_times().to_vec();
+    laps.sort();
+    assert_eq!(laps[0], ctgp_metadata.exact_lap_times()[2]);
+    assert_eq!(
+        (ctgp_metadata.exact_lap_times()[1] - ctgp_metadata.exact_lap_times()[0]).to_string(),
+        "00:00.016570788324"
+    );
+
+    let parsed: ExactFinishTime = "00:58.647988872949".parse().unwrap();
+    assert_eq!(parsed, exact_finish_time);
+    assert_eq!(parsed.to_string(), exact_finish_time.to_string());
+    assert!("00:58.647".parse::<ExactFinishTime>().is_err());
+    assert!(
+        ExactFinishTime::from(*header.finish_time()) > exact_finish_time,
+        "Displayed time is rounded up"
+    );
+
+    // The saturating methods never panic
+    let longest = ExactFinishTime::new(255, 59, 999_999_999_999);
+    assert_eq!(longest, ExactFinishTime::MAX);
+    assert_eq!(longest.saturating_add(parsed), ExactFinishTime::MAX);
+    assert_eq!(longest.checked_add(parsed), None);
+    assert_eq!(parsed.saturating_sub(longest), ExactFinishTime::default());
+    assert_eq!(
+        ExactFinishTime::new(0, 0, u64::MAX).abs_diff(parsed),
+        ExactFinishTime::MAX
+    );
+
+    for path in [
+        "./test_ghosts/01m44s4420944 chϊcκεη.rkg",
+        "./test_ghosts/01m54s9878581 chϊcκεη.rkg",
+    ] {
+        let mut rkg_data: Vec<u8> = Vec::new();
+        std::fs::File::open(path)
+            .expect("Couldn't find ghost")
+            .read_to_end(&mut rkg_data)
+            .expect("Couldn't read bytes in file");
+
+        let header = Header::new(&rkg_data[..0x88]).expect("Couldn't read header");
+        let ctgp_metadata = CTGPMetadata::new(&rkg_data).expect("Failed to read CTGP metadata");
+        assert_eq!(
+            ctgp_metadata.exact_finish_time().to_in_game_time().unwrap(),
+            *header.finish_time()
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "attempt to add with overflow")]
+fn test_exact_finish_time_add_overflow() {
+    let _ = [ExactFinishTime::MAX, ExactFinishTime::new(0, 0, 1)]
+        .iter()
+        .sum::<ExactFinishTime>();
+}
+
+#[test]
+#[should_panic(expected = "attempt to subtract with overflow")]
+fn test_exact_finish_time_sub_overflow() {
+    let _ = ExactFinishTime::new(0, 58, 0) - ExactFinishTime::new(0, 59, 0);
+}
+
+#[test]
+fn test_timing_report() {
+    for path in [
+        "./test_ghosts/JC_LC.rkg",
+        "./test_ghosts/JC_LC_Compressed.rkg",
+        "./test_ghosts/9laps_test.rkg",
+        "./test_ghosts/00m58s6479888 David .rkg",
+        "./test_ghosts/01m44s4420944 chϊcκεη.rkg",
+        "./test_ghosts/01m54s9878581 chϊcκεη.rkg",
+        "./test_ghosts/skylar_pause_ghost_compressed.rkg",
+        "./test_ghosts/skylar_pause_ghost_vanilla.rkg",
+    ] {
+        let mut rkg_data: Vec<u8> = Vec::new();
+        std::fs::File::open(path)
+            .expect("Couldn't find ghost")
+            .read_to_end(&mut rkg_data)
+            .expect("Couldn't read bytes in file");
+
+        let header = Header::new(&rkg_data[..0x88]).expect("Couldn't read header");
+        let input_data = InputData::new(&rkg_data[0x88..]).expect("Couldn't read input data");
+        let ctgp_metadata = CTGPMetadata::new(&rkg_data).ok();
+
+        let report = TimingReport::new(&header, &input_data, ctgp_metadata.as_ref());
+        assert!(report.is_consistent(), "{path}: {:?}", report.issues());
+    }
+
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/JC_LC_Compressed.rkg")
+        .expect("Couldn't find `./test_ghosts/JC_LC_Compressed.rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+
+    let mut header = Header::new(&rkg_data[..0x88]).expect("Couldn't read header");
+    let input_data = InputData::new(&rkg_data[0x88..]).expect("Couldn't read input data");
+    let ctgp_metadata = CTGPMetadata::new(&rkg_data).expect("Failed to read CTGP metadata");
+    assert_eq!(
+        TimingReport::new(&header, &input_data, None).input_frames(),
+        4071
+    );
+
+    // Hex-edit the finish time down by a second
+    header.set_finish_time(InGameTime::new(1, 2, 904));
+    let report = TimingReport::new(&header, &input_data, Some(&ctgp_metadata));
+    assert!(!report.is_consistent());
+    assert!(report.issues().contains(&TimingIssue::LapSumMismatch {
+        finish_time: InGameTime::new(1, 2, 904),
+        lap_sum: InGameTime::new(1, 3, 904),
+    }));
+    assert!(matches!(
+        report.issues()[1],
+        TimingIssue::ExactFinishTimeMismatch { .. }
+    ));
+    // The exact time comes from the untouched footer, so the inputs still agree with it
+    assert_eq!(report.issues().len(), 2);
+
+    let report = TimingReport::new(&header, &input_data, None);
+    assert!(matches!(
+        report.issues()[1],
+        TimingIssue::InputFrameCountMismatch {
+            input_frames: 4071,
+            ..
+        }
+    ));
+    assert_eq!(report.expected_input_frames(), &(4011..=4011));
+
+    // Two laps of 99:59.999 overflow what an `InGameTime` can hold
+    let rkg_data =
+        std::fs::read("./test_ghosts/illegal_brake_input.rkg").expect("Couldn't read ghost");
+    let header = Header::new(&rkg_data[..0x88]).expect("Couldn't read header");
+    let input_data = InputData::new(&rkg_data[0x88..]).expect("Couldn't read input data");
+    let report = TimingReport::new(&header, &input_data, None);
+    assert_eq!(report.lap_sum(), InGameTime::MAX);
+    assert!(report.issues().contains(&TimingIssue::LapSumMismatch {
+        finish_time: *header.finish_time(),
+        lap_sum: InGameTime::MAX,
+    }));
+}
+
+#[test]
+fn test_race_clock() {
+    let race_clock = RaceClock::default();
+    assert_eq!(race_clock.frame_to_time(239), None);
+    assert_eq!(
+        race_clock.frame_to_time(240),
+        Some(ExactFinishTime::default())
+    );
+    assert_eq!(
+        race_clock.frame_to_time(300).unwrap().to_string(),
+        "00:01.001000000000"
+    );
+    assert_eq!(
+        race_clock.time_to_frame("00:01.001000000000".parse().unwrap()),
+        300
+    );
+    assert_eq!(
+        race_clock.time_to_frame("00:01.001000000001".parse().unwrap()),
+        301
+    );
+
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/00m58s6479888 David .rkg")
+        .expect("Couldn't find `./test_ghosts/00m58s6479888 David .rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+
+    let header = Header::new(&rkg_data[..0x88]).expect("Couldn't read header");
+    let input_data = InputData::new(&rkg_data[0x88..]).expect("Couldn't read input data");
+    let ctgp_metadata = CTGPMetadata::new(&rkg_data).expect("Failed to read CTGP metadata");
+
+    // The finish line is crossed during the last frame of input
+    let end_time = input_data.end_time(race_clock).unwrap();
+    let exact_finish_time = ctgp_metadata.exact_finish_time();
+    assert!(end_time >= exact_finish_time);
+    assert!(
+        race_clock
+            .frame_to_time(input_data.frame_count() - 1)
+            .unwrap()
+            < exact_finish_time
+    );
+
+    let lap_boundaries = race_clock.lap_boundaries(&header);
+    assert_eq!(lap_boundaries.len(), 3);
+    assert_eq!(*lap_boundaries.last().unwrap(), input_data.frame_count());
+    assert!(
+        race_clock
+            .in_game_time_to_frames(*header.finish_time())
+            .contains(&3756)
+    );
+
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/skylar_pause_ghost_compressed.rkg")
+        .expect("Couldn't find `./test_ghosts/skylar_pause_ghost_compressed.rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+
+    let ctgp_metadata = CTGPMetadata::new(&rkg_data).expect("Failed to read CTGP metadata");
+    let pause_times = ctgp_metadata.pause_times();
+    assert!(!pause_times.is_empty());
+    for (frame, time) in ctgp_metadata.pause_frames().iter().zip(pause_times) {
+        if let Some(time) = time {
+            assert_eq!(race_clock.time_to_frame(time), *frame);
+        }
+    }
+}
+
+#[test]
+fn test_lap_inputs() {
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/9laps_test.rkg")
+        .expect("Couldn't find `./test_ghosts/9laps_test.rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+
+    let header = Header::new(&rkg_data[..0x88]).expect("Couldn't read header");
+    let input_data = InputData::new(&rkg_data[0x88..]).expect("Couldn't read input data");
+    let laps = input_data.laps(&header);
+
+    assert_eq!(laps.len(), 10);
+    assert_eq!(laps[0].segment(), LapSegment::Countdown);
+    assert_eq!(laps[0].frames(), &(1..=240));
+    assert_eq!(laps[9].segment(), LapSegment::Lap(8));
+    assert_eq!(*laps[9].frames().end(), input_data.frame_count());
+
+    let race_clock = RaceClock::default();
+    let mut previous_end = 0;
+    for (lap, boundary) in laps
+        .iter()
+        .zip(std::iter::once(240).chain(race_clock.lap_boundaries(&header)))
+    {
+        assert_eq!(*lap.frames().start(), previous_end + 1);
+        assert_eq!(*lap.frames().end(), boundary);
+        assert_eq!(
+            lap.inputs().iter().map(|i| i.frame_duration()).sum::<u32>(),
+            lap.frame_count()
+        );
+        previous_end = boundary;
+    }
+
+    // Lap 1 is 4.825 seconds, which is 290 frames at 59.94 Hz
+    assert_eq!(laps[1].frame_count(), 290);
+
+    assert_eq!(
+        laps.iter().map(|lap| lap.frame_count()).sum::<u32>(),
+        input_data.frame_count()
+    );
+    // At most one input is split per lap line
+    assert!(
+        laps.iter().map(|lap| lap.inputs().len()).sum::<usize>()
+            < input_data.inputs().len() + laps.len()
+    );
+}
+
+#[test]
+fn test_verify_integrity() {
+    for path in [
+        "./test_ghosts/JC_LC.rkg",
+        "./test_ghosts/JC_LC_Compressed.rkg",
+        "./test_ghosts/9laps_test.rkg",
+        "./test_ghosts/00m58s6479888 David .rkg",
+        "./test_ghosts/01m54s9878581 chϊcκεη.rkg",
+        "./test_ghosts/skylar_pause_ghost_compressed.rkg",
+    ] {
+        let ghost = Ghost::new_from_file(path).expect("Couldn't read ghost");
+        let report = ghost.verify_integrity();
+        assert!(report.is_intact(), "{path}: {:?}", report.issues());
+    }
+
+    // Footer CRC32 of this ghost is wrong, the RKG data itself is fine
+    let ghost = Ghost::new_from_file("./test_ghosts/01m44s4420944 chϊcκεη.rkg")
+        .expect("Couldn't read ghost");
+    let report = ghost.verify_integrity();
+    assert_eq!(report.issues().len(), 1);
+    assert!(matches!(
+        report.issues()[0],
+        IntegrityIssue::FooterCrc32Mismatch { offset, .. } if offset == ghost.bytes().len() - 4
+    ));
+
+    // Raw save slot: ghost without a CRC32 of its own, followed by zero padding and the slot CRC32
+    let mut slot_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/skylar_pause_ghost_vanilla.rkg")
+        .expect("Couldn't find `./test_ghosts/skylar_pause_ghost_vanilla.rkg`")
+        .read_to_end(&mut slot_data)
+        .expect("Couldn't read bytes in file");
+    assert_eq!(
+        verify_integrity(&slot_data).issues(),
+        [
+            IntegrityIssue::Crc32Mismatch {
+                offset: 0x36C,
+                stored: 0,
+                computed: crc32(&slot_data[..0x36C])
+            },
+            IntegrityIssue::TrailingData {
+                offset: 0x370,
+                length: 0x2800 - 0x370
+            }
+        ]
+    );
+
+    let mut rkg_data = Vec::from(ghost.bytes());
+    rkg_data[0x3E] ^= 0x01; // Mii name
+    let metadata_size_offset = rkg_data.len() - 0x0C;
+    rkg_data[metadata_size_offset + 3] = 0xD0;
+    rkg_data.truncate(0x200);
+    let issues = verify_integrity(&rkg_data).issues().to_vec();
+    assert!(matches!(
+        issues[0],
+        IntegrityIssue::MiiCrc16Mismatch { offset: 0x86, .. }
+    ));
+    assert!(matches!(issues[1], IntegrityIssue::Truncated { .. }));
+
+    let mut rkg_data = Vec::from(ghost.bytes());
+    let metadata_size_offset = rkg_data.len() - 0x0C;
+    rkg_data[metadata_size_offset + 3] = 0xD0;
+    let issues = verify_integrity(&rkg_data).issues().to_vec();
+    assert!(issues.contains(&IntegrityIssue::MetadataSizeMismatch {
+        offset: metadata_size_offset,
+        metadata_size: 0xD0,
+        expected: 0xE0,
+    }));
+
+    let mut rkg_data = Vec::from(ghost.bytes());
+    rkg_data[0x0E..0x10].copy_from_slice(&0x0700_u16.to_be_bytes());
+    let issues = verify_integrity(&rkg_data).issues().to_vec();
+    assert!(issues.contains(&IntegrityIssue::InputDataLengthMismatch {
+        offset: 0x0E,
+        declared: 0x700,
+        actual: 0x758,
+    }));
+    assert!(matches!(
+        issues.last(),
+        Some(IntegrityIssue::Crc32Mismatch { .. })
+    ));
+}
+
+#[test]
+fn test_malformed_ghosts() {
+    use crate::{
+        GhostError,
+        ctgp_metadata::CTGPMetadataError,
+        input_data::InputDataError,
+        leaderboard::{BoardKind, Leaderboard},
+    };
+
+    let rkg_data = std::fs::read("./test_ghosts/JC_LC.rkg").expect("Couldn't read ghost");
+    let compressed_data =
+        std::fs::read("./test_ghosts/JC_LC_Compressed.rkg").expect("Couldn't read ghost");
+
+    // Face input count far past the end of the data
+    let mut oversized_counts = rkg_data.clone();
+    oversized_counts[0x88..0x8A].copy_from_slice(&[0xFF, 0xFF]);
+    assert!(matches!(
+        Ghost::new(&oversized_counts),
+        Err(GhostError::InputDataError(_))
+    ));
+    assert!(matches!(
+        Ghost::new(&rkg_data[..0x88 + 0x0C]),
+        Err(GhostError::InputDataError(InputDataError::TooShort))
+    ));
+
+    // First chunk copies from before the start of the output
+    let mut corrupt_yaz1 = compressed_data.clone();
+    corrupt_yaz1[0x8C + 0x10..0x8C + 0x13].copy_from_slice(&[0x00, 0x00, 0x00]);
+    assert!(matches!(
+        Ghost::new(&corrupt_yaz1),
+        Err(GhostError::InputDataError(InputDataError::InvalidYaz1))
+    ));
+    assert!(InputData::new(&[0x00, 0x00, 0x00, 0x10]).is_err());
+
+    // Ends like a CTGP footer, but its size doesn't fit the file or its fields
+    for metadata_size in [0xFFFF_FFFF_u32, 0x10] {
+        let mut fake_footer = rkg_data.clone();
+        fake_footer.push(0x07);
+        fake_footer.extend_from_slice(&metadata_size.to_be_bytes());
+        fake_footer.extend_from_slice(b"CKGD");
+        fake_footer.extend_from_slice(&crc32(&fake_footer).to_be_bytes());
+        assert!(matches!(
+            Ghost::new(&fake_footer),
+            Err(GhostError::CTGPMetadataError(CTGPMetadataError::InvalidMetadataSize(size)))
+                if size == metadata_size
+        ));
+        assert!(audit(&fake_footer).has_code("ctgp.invalid"));
+    }
+    assert!(matches!(
+        CTGPMetadata::new(b"CKGD1234"),
+        Err(CTGPMetadataError::TooShort)
+    ));
+
+    // Only 10 split times are stored, whatever the lap count says
+    let mut many_laps = rkg_data.clone();
+    many_laps[0x10] = 0xFF;
+    let ghost = Ghost::new(&many_laps).expect("Couldn't read ghost");
+    assert_eq!(ghost.header().lap_count(), 0xFF);
+    assert_eq!(ghost.header().lap_split_times().len(), 10);
+    assert_eq!(Leaderboard::build(&[ghost], BoardKind::FastestLap).len(), 1);
+    audit(&many_laps);
+}
+
+#[test]
+fn test_audit() {
+    let ghost =
+        Ghost::new_from_file("./test_ghosts/JC_LC_Compressed.rkg").expect("Couldn't read ghost");
+    let report = ghost.audit();
+    assert_eq!(report.verdict(), Verdict::Accept, "{:?}", report.findings());
+
+    let ghost = Ghost::new_from_file("./test_ghosts/01m44s4420944 chϊcκεη.rkg")
+        .expect("Couldn't read ghost");
+    let report = ghost.audit();
+    assert_eq!(report.verdict(), Verdict::Review);
+    assert!(report.has_code("integrity.footer_crc32_mismatch"));
+
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/illegal_drift_inputs.rkg")
+        .expect("Couldn't find `./test_ghosts/illegal_drift_inputs.rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+    assert!(Ghost::new(&rkg_data).is_err());
+    let report = audit(&rkg_data);
+    assert_eq!(report.verdict(), Verdict::Reject);
+    assert!(report.has_code("input.illegal_drift"));
+
+    // Hand-edited Mii data needs a look even when the rest of the ghost is fine
+    let rkg_data =
+        std::fs::read("./test_ghosts/illegal_brake_input.rkg").expect("Couldn't read ghost");
+    let report = audit(&rkg_data);
+    let mii_findings: Vec<_> = report
+        .findings()
+        .iter()
+        .filter(|finding| finding.code().starts_with("mii."))
+        .map(|finding| (finding.code(), finding.severity()))
+        .collect();
+    assert_eq!(
+        mii_findings,
+        [
+            ("mii.empty_name", Severity::Info),
+            ("mii.out_of_range", Severity::Review),
+            ("mii.special_mii_mingles", Severity::Review),
+        ]
+    );
+
+    // Hex-edited finish time, with the CRC32s fixed up so only the times give it away
+    let mut rkg_data = Vec::from(ghost.bytes());
+    rkg_data[0x04..0x07].copy_from_slice(&InGameTime::new(1, 43, 443).to_bytes().unwrap());
+    let crc_offset = rkg_data.len() - 0xE0 - 0x08;
+    let crc = crc32(&rkg_data[..crc_offset]);
+    rkg_data[crc_offset..crc_offset + 4].copy_from_slice(&crc.to_be_bytes());
+    let footer_crc_offset = rkg_data.len() - 0x04;
+    let crc = crc32(&rkg_data[..footer_crc_offset]);
+    rkg_data[footer_crc_offset..].copy_from_slice(&crc.to_be_bytes());
+
+    let report = audit(&rkg_data);
+    assert_eq!(report.verdict(), Verdict::Reject);
+    assert!(!report.has_code("integrity.crc32_mismatch"));
+    assert!(report.has_code("timing.lap_sum_mismatch"));
+    // CTGP's exact time is an offset from the header time, so only the inputs still disagree
+    assert!(report.has_code("timing.input_frame_count_mismatch"));
+    assert!(
+        report
+            .findings()
+            .iter()
+            .filter(|finding| finding.code().starts_with("timing."))
+            .all(|finding| finding.severity() == Severity::Reject)
+    );
+}
+
+#[test]
+fn test_combo_validation() {
+    assert!(Character::MenuRosalina.is_impossible());
+    assert!(Character::LargeMii.is_impossible());
+    assert!(!Character::KingBoo.is_impossible());
+    assert!(matches!(
+        Combo::new(Vehicle::WarioBike, Character::MenuRosalina),
+        Err(ComboError::ImpossibleCharacterId)
+    ));
+    assert!(matches!(
+        Combo::new(Vehicle::WarioBike, Character::BabyMario),
+        Err(ComboError::IncongruentWeightClasses)
+    ));
+    assert_eq!(
+        Combo::new_unchecked(Vehicle::WarioBike, Character::BabyMario).issues(),
+        [ComboIssue::IncongruentWeightClasses {
+            character: Character::BabyMario,
+            vehicle: Vehicle::WarioBike
+        }]
+    );
+
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/JC_LC.rkg")
+        .expect("Couldn't find `./test_ghosts/JC_LC.rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+    let is_girl = Header::new(&rkg_data[..0x88])
+        .expect("Couldn't read header")
+        .mii()
+        .is_girl();
+    let set_character = |rkg_data: &mut [u8], character: Character| {
+        let id = u8::from(character);
+        rkg_data[0x08] = (rkg_data[0x08] & 0xFC) | (id >> 4);
+        rkg_data[0x09] = (rkg_data[0x09] & 0x0F) | (id << 4);
+    };
+
+    set_character(&mut rkg_data, Character::MenuRosalina);
+    assert!(matches!(
+        Header::new(&rkg_data[..0x88]),
+        Err(HeaderError::ComboError(ComboError::ImpossibleCharacterId))
+    ));
+    let header = Header::new_with_combo_validation(&rkg_data[..0x88], ComboValidation::Warn)
+        .expect("Couldn't read header");
+    assert_eq!(header.combo().character(), Character::MenuRosalina);
+    assert_eq!(
+        header.combo_issues(),
+        [ComboIssue::ImpossibleCharacter(Character::MenuRosalina)]
+    );
+    let header = Header::new_with_combo_validation(&rkg_data[..0x88], ComboValidation::Permissive)
+        .expect("Couldn't read header");
+    assert!(header.combo_issues().is_empty());
+    assert!(audit(&rkg_data).has_code("combo.impossible_character"));
+
+    // Outfit for the other gender than JC's Mii, still readable in strict mode
+    let outfit = if is_girl {
+        Character::LargeMiiOutfitAMale
+    } else {
+        Character::LargeMiiOutfitAFemale
+    };
+    assert_eq!(outfit.get_weight_class(), WeightClass::Large);
+    set_character(&mut rkg_data, outfit);
+    let header = Header::new(&rkg_data[..0x88]).expect("Couldn't read header");
+    assert_eq!(
+        header.combo_issues(),
+        [ComboIssue::MiiOutfitMismatch {
+            character: outfit,
+            is_girl
+        }]
+    );
+    assert!(audit(&rkg_data).has_code("combo.mii_outfit_mismatch"));
+}
+
+#[test]
+fn test_raw_bits() {
+    for value in 0x07..=0x24 {
+        let ghost_type = GhostType::try_from(value).expect("Couldn't read friend ghost type");
+        assert_eq!(ghost_type, GhostType::Friend(value - 0x07));
+        assert_eq!(u8::try_from(ghost_type).unwrap(), value);
+    }
+    assert!(matches!(
+        u8::try_from(GhostType::Friend(30)),
+        Err(GhostTypeError::FriendIndexOutOfRange)
+    ));
+
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/00m58s6479888 David .rkg")
+        .expect("Couldn't find `./test_ghosts/00m58s6479888 David .rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+
+    let header = Header::new(&rkg_data[..0x88]).expect("Couldn't read header");
+    assert_eq!(header.country_id(), 0x6E);
+    assert_eq!(header.location_bytes(), &rkg_data[0x34..0x3C]);
+    assert_eq!(header.unknown_flags(), 0);
+
+    // Unknown bits next to the ghost type don't change it
+    rkg_data[0x0C] |= 0x82;
+    rkg_data[0x0D] |= 0x01;
+    let header = Header::new(&rkg_data[..0x88]).expect("Couldn't read header");
+    assert_eq!(header.ghost_type(), GhostType::ExpertStaff);
+    assert_eq!(header.unknown_flags(), 0x8201);
+
+    assert_eq!(
+        parse_face_buttons(0x81).unwrap(),
+        [FaceButton::Accelerator, FaceButton::Unknown(0x80)]
+    );
+
+    let mut pause_rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/skylar_pause_ghost_compressed.rkg")
+        .expect("Couldn't find `./test_ghosts/skylar_pause_ghost_compressed.rkg`")
+        .read_to_end(&mut pause_rkg_data)
+        .expect("Couldn't read bytes in file");
+    let pause_inputs = InputData::new(&pause_rkg_data[0x88..pause_rkg_data.len() - 0xE0])
+        .expect("Failed to read inputs from pause ghost");
+    let raw_input_data = pause_inputs.raw_input_data();
+    let face_bytes = &raw_input_data[0x08..0x08 + pause_inputs.face_input_count() as usize * 2];
+    assert!(face_bytes.chunks_exact(2).any(|input| input[0] & 0x40 != 0));
+
+    // Only the pause mask is dropped, unknown bits next to it survive a round trip
+    for value in [0x40, 0x41, 0x43, 0x4B, 0xC1, 0x70, 0xF5] {
+        let buttons = parse_face_buttons(value).unwrap();
+        assert_eq!(face_buttons_to_byte(&buttons), value & !0x40, "{value:#X}");
+    }
+    assert_eq!(
+        parse_face_buttons(0xC0).unwrap(),
+        [FaceButton::Unknown(0x80)]
+    );
+    assert_eq!(
+        yaz1_decompress(&pause_rkg_data[0x8C..]).as_deref(),
+        Some(raw_input_data)
+    );
+}
+
+#[test]
+fn test_bit_layout() {
+    use crate::header::{layout::HEADER_FIELDS, mii::layout as mii_layout};
+
+    let field = HEADER_FIELDS[2];
+    assert_eq!(
+        (field.name(), field.offset(), field.bit()),
+        ("FINISH_SECONDS", 0x04, 7)
+    );
+    let mut data = [0xFF_u8; 7];
+    field.write(&mut data, 0).unwrap();
+    assert_eq!(data[0x04..], [0xFE, 0x03, 0xFF]);
+    assert_eq!(field.read(&data).unwrap(), 0);
+    assert!(field.write(&mut data, 0x80).is_err());
+    assert!(field.offset_by(2).read(&data).is_err());
+
+    for entry in std::fs::read_dir("./test_ghosts").expect("Couldn't find `./test_ghosts`") {
+        let mut rkg_data: Vec<u8> = Vec::new();
+        std::fs::File::open(entry.unwrap().path())
+            .expect("Couldn't open ghost")
+            .read_to_end(&mut rkg_data)
+            .expect("Couldn't read bytes in file");
+        let Ok(mut header) = Header::new(&rkg_data[..0x88]) else {
+            continue;
+        };
+        assert_eq!(header.to_bytes().unwrap(), rkg_data[..0x88]);
+
+        let mii_data = &rkg_data[0x3C..0x86];
+        let mii = header.mii();
+        let read = |field: crate::layout::BitField| field.read(mii_data).unwrap() as u8;
+        assert_eq!(read(mii_layout::IS_GIRL) != 0, mii.is_girl());
+        assert_eq!(read(mii_layout::MINGLE_OFF) != 0, mii.mingle_off());
+        assert_eq!(read(mii_layout::DOWNLOADED) != 0, mii.downloaded());
+        assert_eq!(read(mii_layout::HAIR_FLIPPED) != 0, mii.hair().is_flipped());
+        assert_eq!(read(mii_layout::HAS_MOLE) != 0, mii.mole().has_mole());
+        assert_eq!(
+            mii_layout::MII_ID.read(mii_data).unwrap(),
+            mii.mii_id().raw()
+        );
+        assert_eq!(read(mii_layout::HEIGHT), mii.build().height());
+        assert_eq!(
+            FavColor::try_from(read(mii_layout::FAVORITE_COLOR)).ok(),
+            Some(mii.favorite_color())
+        );
+        assert_eq!(
+            HeadShape::try_from(read(mii_layout::HEAD_SHAPE)).ok(),
+            Some(mii.head().shape())
+        );
+        assert_eq!(
+            HairType::try_from(read(mii_layout::HAIR_TYPE)).ok(),
+            Some(mii.hair().hair_type())
+        );
+        assert_eq!(
+            HairColor::try_from(read(mii_layout::HAIR_COLOR)).ok(),
+            Some(mii.hair().hair_color())
+        );
+        assert_eq!(
+            EyebrowType::try_from(read(mii_layout::EYEBROW_TYPE)).ok(),
+            Some(mii.eyebrows().eyebrow_type())
+        );
+        assert_eq!(
+            read(mii_layout::EYEBROW_ROTATION),
+            mii.eyebrows().rotation()
+        );
+        assert_eq!(read(mii_layout::EYEBROW_SIZE), mii.eyebrows().size());
+        assert_eq!(read(mii_layout::EYEBROW_Y), mii.eyebrows().y());
+        assert_eq!(read(mii_layout::EYEBROW_X), mii.eyebrows().x());
+        assert_eq!(
+            EyeType::try_from(read(mii_layout::EYE_TYPE)).ok(),
+            Some(mii.eyes().eye_type())
+        );
+        assert_eq!(read(mii_layout::EYE_ROTATION), mii.eyes().rotation());
+        assert_eq!(read(mii_layout::EYE_SIZE), mii.eyes().size());
+        assert_eq!(read(mii_layout::EYE_Y), mii.eyes().y());
+        assert_eq!(read(mii_layout::EYE_X), mii.eyes().x());
+        assert_eq!(read(mii_layout::NOSE_SIZE), mii.nose().size());
+        assert_eq!(read(mii_layout::NOSE_Y), mii.nose().y());
+        assert_eq!(read(mii_layout::LIPS_SIZE), mii.lips().size());
+        assert_eq!(read(mii_layout::LIPS_Y), mii.lips().y());
+        assert_eq!(read(mii_layout::GLASSES_SIZE), mii.glasses().size());
+        assert_eq!(read(mii_layout::GLASSES_Y), mii.glasses().y());
+        assert_eq!(
+            read(mii_layout::MUSTACHE_SIZE),
+            mii.facial_hair().mustache_size()
+        );
+        assert_eq!(read(mii_layout::MUSTACHE_Y), mii.facial_hair().mustache_y());
+        assert_eq!(
+            BeardType::try_from(read(mii_layout::BEARD_TYPE)).ok(),
+            Some(mii.facial_hair().beard_type())
+        );
+        assert_eq!(
+            MustacheType::try_from(read(mii_layout::MUSTACHE_TYPE)).ok(),
+            Some(mii.facial_hair().mustache_type())
+        );
+        assert_eq!(read(mii_layout::MOLE_SIZE), mii.mole().size());
+        assert_eq!(read(mii_layout::MOLE_Y), mii.mole().y());
+        assert_eq!(read(mii_layout::MOLE_X), mii.mole().x());
+
+        // Edited fields are written where they were read from
+        header.set_finish_time(InGameTime::new(1, 2, 345));
+        header.set_ghost_type(GhostType::Friend(3));
+        let bytes = header.to_bytes().unwrap();
+        let edited = Header::new(&bytes).expect("Couldn't read edited header");
+        assert_eq!(edited.finish_time(), &InGameTime::new(1, 2, 345));
+        assert_eq!(edited.ghost_type(), GhostType::Friend(3));
+        assert_eq!(bytes[0x07..0x0C], rkg_data[0x07..0x0C]);
+        assert_eq!(bytes[0x0E..], rkg_data[0x0E..0x88]);
+
+        header.set_ghost_type(GhostType::Friend(30));
+        assert!(matches!(
+            header.to_bytes(),
+            Err(HeaderError::GhostTypeError(
+                GhostTypeError::FriendIndexOutOfRange
+            ))
+        ));
+    }
+}
+
+#[test]
+fn test_byte_handler() {
+    let bytes = [0x12, 0x34, 0x56, 0x78];
+    let handler = ByteHandler::from(bytes);
+    assert_eq!(handler.copy_dword(), 0x12345678);
+    assert_eq!(
+        [0, 1, 2, 3].map(|idx| handler.copy_byte(idx)),
+        [0x12, 0x34, 0x56, 0x78]
+    );
+    assert_eq!(handler.copy_byte(4), 0);
+
+    // Shorter data is left-aligned
+    assert_eq!(ByteHandler::from(0xAB_u8).copy_dword(), 0xAB000000);
+    assert_eq!(ByteHandler::from(0xABCD_u16).copy_dword(), 0xABCD0000);
+    assert_eq!(
+        ByteHandler::from([0xAB, 0xCD, 0xEF]).copy_dword(),
+        0xABCDEF00
+    );
+    assert_eq!(ByteHandler::from([0x1234_u16, 0x5678]), handler);
+    assert_eq!(ByteHandler::try_from(&bytes[..]).unwrap(), handler);
+    assert_eq!(
+        ByteHandler::try_from(&bytes[..2]).unwrap().copy_dword(),
+        0x12340000
+    );
+    assert!(ByteHandler::try_from(&[0_u8; 5][..]).is_err());
+    assert_eq!(
+        ByteHandler::try_from(&[0x1234_u16, 0x5678][..]).unwrap(),
+        handler
+    );
+
+    let mut shifted = handler;
+    shifted.shift_right(4);
+    assert_eq!(shifted.copy_dword(), 0x01234567);
+    shifted.shift_right(32);
+    assert_eq!(shifted.copy_dword(), 0);
+
+    // Bit 3 of byte 0 and bit 1 of byte 1
+    let flags = ByteHandler::from([0x08_u8, 0x02]);
+    assert!(flags.read_bool(3));
+    assert!(flags.read_bool(9));
+    assert_eq!((0..32).filter(|d| flags.read_bool(*d)).count(), 2);
+    assert!(!flags.read_bool(32));
+
+    // Layout fields are read with their offset counted from where the handled bytes start
+    assert_eq!(
+        handler
+            .read_field(crate::header::mii::layout::EYEBROW_X, 0x24)
+            .unwrap(),
+        0x8
+    );
+
+    // The packed fields of the header decode the same as their layout describes
+    let time = InGameTime::from_byte_handler(&[0x02_u8, 0x0F, 0x88][..]).unwrap();
+    assert_eq!(time, InGameTime::new(1, 3, 904));
+    assert_eq!(time.to_bytes().unwrap(), [0x02, 0x0F, 0x88]);
+    // Seconds only get 7 bits, so they can't be cut down to fit
+    assert!(InGameTime::new(0, 128, 0).to_bytes().is_err());
+}
+
+/// Same results whatever the host's byte order. Run it on a big-endian target as described in
+/// the README's Testing section.
+#[test]
+fn test_byte_handler_cross_endian() {
+    use crate::header::mii::layout::MII_FIELDS;
+
+    for value in [0x12345678_u32, 0x80000001, 0x00FF00FF, u32::MAX, 0] {
+        let handler = ByteHandler::from(value.to_be_bytes());
+        assert_eq!(handler.copy_dword(), value);
+        assert_eq!(
+            [0, 1, 2, 3].map(|idx| handler.copy_byte(idx)),
+            value.to_be_bytes()
+        );
+        assert_eq!(ByteHandler::from(value), handler);
+        assert_eq!(
+            ByteHandler::from(value.to_le_bytes()).copy_dword(),
+            value.swap_bytes()
+        );
+
+        // Native bytes only match big-endian ones on a big-endian host
+        let native = ByteHandler::from(value.to_ne_bytes()).copy_dword();
+        if cfg!(target_endian = "big") {
+            assert_eq!(native, value);
+        } else {
+            assert_eq!(native, value.swap_bytes());
+        }
+    }
+
+    // Every Mii field written to the bytes reads back the same through a handler
+    for field in MII_FIELDS.iter().copied() {
+        for fill in [0x00, 0xFF] {
+            let mut bytes = [fill; 4];
+            let value = 0xA5A5A5A5 & field.max_value();
+            let field_at_start = field.relative_to(field.offset());
+            field_at_start.write(&mut bytes, value).unwrap();
+
+            let handler = ByteHandler::from(bytes);
+            assert_eq!(
+                handler.read_field(field, field.offset()).unwrap(),
+                value,
+                "{}",
+                field.name()
+            );
+            assert_eq!(handler.copy_dword().to_be_bytes(), bytes);
+        }
+    }
+}
+
+/// Decodes a field, checks encoding it again changes nothing and that neighbouring bits don't
+/// affect it
+fn check_field_codec<T: FieldCodec>(data: &[u8], offset: usize)
+where
+    T::Error: std::fmt::Debug,
+{
+    let value = T::decode(&data[offset..]).expect("Couldn't decode field");
+    let mut copy = data.to_vec();
+    value.encode(&mut copy[offset..]).unwrap();
+    assert_eq!(copy, data);
+
+    for fill in [0x00, 0xFF] {
+        let mut bytes = vec![fill; T::SIZE];
+        value.encode(&mut bytes).unwrap();
+        let mut encoded_again = vec![fill; T::SIZE];
+        T::decode(&bytes)
+            .expect("Couldn't decode encoded field")
+            .encode(&mut encoded_again)
+            .unwrap();
+        assert_eq!(bytes, encoded_again);
+    }
+}
+
+#[test]
+fn test_field_codec() {
+    use crate::header::mii::{
+        bday::Birthday, build::Build, eyebrows::Eyebrows, eyes::Eyes, facial_hair::FacialHair,
+        glasses::Glasses, hair::Hair, head::Head, lips::Lips, mii_id::MiiId, mole::Mole,
+        nose::Nose, system_id::SystemId,
+    };
+
+    for entry in std::fs::read_dir("./test_ghosts").expect("Couldn't find `./test_ghosts`") {
+        let mut rkg_data: Vec<u8> = Vec::new();
+        std::fs::File::open(entry.unwrap().path())
+            .expect("Couldn't open ghost")
+            .read_to_end(&mut rkg_data)
+            .expect("Couldn't read bytes in file");
+        if Header::new(&rkg_data[..0x88]).is_err() {
+            continue;
+        }
+
+        let header = &rkg_data[..0x88];
+        check_field_codec::<InGameTime>(header, 0x04);
+        check_field_codec::<SlotId>(header, 0x07);
+        check_field_codec::<Combo>(header, 0x08);
+        check_field_codec::<Date>(header, 0x09);
+        check_field_codec::<Controller>(header, 0x0B);
+        check_field_codec::<GhostType>(header, 0x0C);
+        check_field_codec::<InGameTime>(header, 0x11);
+
+        let mii = &rkg_data[0x3C..0x86];
+        check_field_codec::<Birthday>(mii, 0x00);
+        check_field_codec::<FavColor>(mii, 0x01);
+        check_field_codec::<Build>(mii, 0x16);
+        check_field_codec::<MiiId>(mii, 0x18);
+        check_field_codec::<SystemId>(mii, 0x1C);
+        check_field_codec::<Head>(mii, 0x20);
+        check_field_codec::<Hair>(mii, 0x22);
+        check_field_codec::<Eyebrows>(mii, 0x24);
+        check_field_codec::<Eyes>(mii, 0x28);
+        check_field_codec::<Nose>(mii, 0x2C);
+        check_field_codec::<Lips>(mii, 0x2E);
+        check_field_codec::<Glasses>(mii, 0x30);
+        check_field_codec::<FacialHair>(mii, 0x32);
+        check_field_codec::<Mole>(mii, 0x34);
+    }
+
+    let mut bytes = [0x00, 0x00, 0x00];
+    InGameTime::new(1, 3, 904).encode(&mut bytes).unwrap();
+    assert_eq!(bytes, [0x02, 0x0F, 0x88]);
+    assert_eq!(
+        InGameTime::decode(&bytes).unwrap(),
+        InGameTime::new(1, 3, 904)
+    );
+    assert!(matches!(
+        InGameTime::decode(&bytes[..2]),
+        Err(InGameTimeError::LayoutError(_))
+    ));
+    assert!(InGameTime::new(200, 0, 0).encode(&mut bytes).is_err());
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn test_wasm_ghost_view() {
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/JC_LC_Compressed.rkg")
+        .expect("Couldn't find `./test_ghosts/JC_LC_Compressed.rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+
+    let view = crate::wasm::ghost_view(&rkg_data).expect("Couldn't parse ghost");
+    assert_eq!(view.header.finish_time.text, "01:03.904");
+    assert_eq!(view.header.track, "LuigiCircuit");
+    assert_eq!(view.header.character, "KingBoo");
+    assert_eq!(view.header.vehicle, "WarioBike");
+    assert_eq!(view.header.controller, "Classic");
+    assert!(view.header.is_automatic_drift);
+    assert_eq!(view.header.lap_split_times[0].text, "00:25.540");
+    assert_eq!(
+        view.input_data
+            .inputs
+            .iter()
+            .map(|input| input.frames)
+            .sum::<u32>(),
+        view.input_data.frame_count
+    );
+    // One entry per frame, the same records as the Python bindings
+    let records = Ghost::new(&rkg_data).unwrap().input_data().frame_records();
+    assert_eq!(
+        view.input_data.frame_inputs.len(),
+        view.input_data.frame_count as usize
+    );
+    assert!(
+        view.input_data
+            .frame_inputs
+            .iter()
+            .zip(records.chunks_exact(4))
+            .all(|(frame, record)| [
+                frame.buttons,
+                frame.stick_x as u8,
+                frame.stick_y as u8,
+                frame.dpad
+            ] == record)
+    );
+
+    let ctgp_metadata = view.ctgp_metadata.expect("Missing CTGP metadata");
+    assert_eq!(ctgp_metadata.player_id, "FD3197B07D9D2B84");
+    assert!(ctgp_metadata.track_sha1.starts_with("1AE1A7D8"));
+    assert!(view.integrity.is_intact);
+    assert_eq!(view.audit.verdict, "Accept");
+
+    assert!(crate::wasm::ghost_view(&rkg_data[..0x40]).is_err());
+}
+
+/// Headless tests for the JS side, run with `wasm-pack test --node --features wasm`
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+mod wasm {
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use crate::wasm::{audit_ghost, parse_ghost, parse_header, verify_ghost_integrity};
+
+    const JC_LC_COMPRESSED: &[u8] = include_bytes!("../test_ghosts/JC_LC_Compressed.rkg");
+
+    fn get(value: &JsValue, path: &[&str]) -> JsValue {
+        path.iter().fold(value.clone(), |value, key| {
+            js_sys::Reflect::get(&value, &JsValue::from_str(key)).unwrap()
+        })
+    }
+
+    #[wasm_bindgen_test]
+    fn parse_ghost_returns_plain_objects() {
+        let ghost = parse_ghost(JC_LC_COMPRESSED).unwrap();
+        assert_eq!(
+            get(&ghost, &["header", "finishTime", "text"]).as_string(),
+            Some("01:03.904".into())
+        );
+        assert_eq!(
+            get(&ghost, &["header", "character"]).as_string(),
+            Some("KingBoo".into())
+        );
+        assert_eq!(
+            get(&ghost, &["ctgpMetadata", "playerId"]).as_string(),
+            Some("FD3197B07D9D2B84".into())
+        );
+        assert!(js_sys::Array::is_array(&get(
+            &ghost,
+            &["inputData", "inputs"]
+        )));
+        assert_eq!(
+            get(&ghost, &["inputData", "frameInputs", "length"]).as_f64(),
+            get(&ghost, &["inputData", "frameCount"]).as_f64()
+        );
+        assert_eq!(
+            get(&ghost, &["integrity", "isIntact"]).as_bool(),
+            Some(true)
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn broken_files_still_audit() {
+        let truncated = &JC_LC_COMPRESSED[..0x90];
+        assert!(parse_ghost(truncated).is_err());
+        assert!(parse_header(truncated).is_ok());
+        assert_eq!(
+            get(&verify_ghost_integrity(truncated).unwrap(), &["isIntact"]).as_bool(),
+            Some(false)
+        );
+        assert_eq!(
+            get(&audit_ghost(truncated).unwrap(), &["verdict"]).as_string(),
+            Some("Reject".into())
+        );
+    }
+}
+
+#[cfg(feature = "python")]
+#[test]
+fn test_python_bindings() {
+    use pyo3::{ffi::c_str, prelude::*, types::PyDict};
+
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/JC_LC_Compressed.rkg")
+        .expect("Couldn't find `./test_ghosts/JC_LC_Compressed.rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+
+    let ghost = Ghost::new(&rkg_data).expect("Couldn't parse ghost");
+    let records = ghost.input_data().frame_records();
+    assert_eq!(records.len(), ghost.input_data().frame_count() as usize * 4);
+
+    Python::initialize();
+    Python::attach(|py| {
+        let module = PyModule::new(py, "rkg_utils").unwrap();
+        crate::python::python_module(&module).unwrap();
+        let locals = PyDict::new(py);
+        locals.set_item("rkg_utils", module).unwrap();
+        locals.set_item("data", rkg_data.as_slice()).unwrap();
+        py.run(
+            c_str!(
+                r#"
+import struct
+ghost = rkg_utils.Ghost(data)
+assert ghost.header.finish_time == "01:03.904"
+assert ghost.header.character == "KingBoo"
+assert ghost.header.lap_split_times == ["00:25.540", "00:19.127", "00:19.237"]
+assert ghost.header.mii.name == ghost.header.mii.display_name
+assert ghost.ctgp_metadata.player_id == 0xFD3197B07D9D2B84
+assert ghost.verify_integrity() == []
+
+frames = ghost.input_data.frame_inputs()
+assert len(frames) == ghost.input_data.frame_count * 4
+assert [name for name, _ in rkg_utils.InputData.FRAME_DTYPE] == ["buttons", "stick_x", "stick_y", "dpad"]
+_, stick_x, stick_y, _ = struct.unpack_from("BbbB", frames, 0)
+assert -7 <= stick_x <= 7 and -7 <= stick_y <= 7
+
+try:
+    rkg_utils.Ghost(data[:0x40])
+    raise AssertionError("short ghost parsed")
+except rkg_utils.RkgError:
+    pass
+"#
+            ),
+            None,
+            Some(&locals),
+        )
+        .unwrap();
+    });
+}
+
+#[cfg(feature = "capi")]
+#[test]
+fn test_capi() {
+    use crate::capi::*;
+
+    let mut rkg_data: Vec<u8> = Vec::new();
+    std::fs::File::open("./test_ghosts/JC_LC_Compressed.rkg")
+        .expect("Couldn't find `./test_ghosts/JC_LC_Compressed.rkg`")
+        .read_to_end(&mut rkg_data)
+        .expect("Couldn't read bytes in file");
+    let inputs = Ghost::new(&rkg_data).unwrap().input_data().inputs();
+
+    unsafe {
+        let ghost = rkg_ghost_parse(rkg_data.as_ptr(), rkg_data.len());
+        assert!(!ghost.is_null());
+        assert_eq!(
+            rkg_ghost_finish_time(ghost),
+            RkgTime {
+                minutes: 1,
+                seconds: 3,
+                milliseconds: 904
+            }
+        );
+        assert_eq!(rkg_ghost_lap_count(ghost), 3);
+        assert_eq!(rkg_ghost_lap_time(ghost, 1).milliseconds, 127);
+        assert_eq!(rkg_ghost_lap_time(ghost, 3), RkgTime::default());
+
+        // Every frame of a run has the run's inputs, and nothing exists past the last one
+        let mut frame = 0;
+        let mut out = RkgFrame::default();
+        for input in &inputs {
+            for _ in 0..input.frame_duration() {
+                assert!(rkg_ghost_frame_at(ghost, frame, &mut out));
+                assert_eq!(out.stick_x, input.stick_x());
+                assert_eq!(out.stick_y, input.stick_y());
+                assert_eq!(out.dpad, u8::from(input.dpad_button()));
+                frame += 1;
+            }
+        }
+        assert_eq!(rkg_ghost_frame_count(ghost), frame);
+        assert!(!rkg_ghost_frame_at(ghost, frame, &mut out));
+        assert!(!rkg_ghost_frame_at(ghost, 0, core::ptr::null_mut()));
+        rkg_ghost_free(ghost);
+
+        assert!(rkg_ghost_parse(rkg_data.as_ptr(), 0x40).is_null());
+        let mut fake_footer = rkg_data[..rkg_data.len() - 0x0C].to_vec();
+        fake_footer.extend_from_slice(&[0xFF; 4]);
+        fake_footer.extend_from_slice(&rkg_data[rkg_data.len() - 0x08..]);
+        assert!(rkg_ghost_parse(fake_footer.as_ptr(), fake_footer.len()).is_null());
+        assert!(rkg_ghost_parse(core::ptr::null(), 0).is_null());
+        assert_eq!(rkg_ghost_frame_count(core::ptr::null()), 0);
+    }
+}
+
+#[test]
+fn test_streaming() {
+    use std::io::{Cursor, ErrorKind};
+
+    use crate::{GhostError, input_data::InputData};
+
+    for path in [
+        "./test_ghosts/JC_LC.rkg",
+        "./test_ghosts/JC_LC_Compressed.rkg",
+        "./test_ghosts/9laps_test.rkg",
+    ] {
+        let mut rkg_data: Vec<u8> = Vec::new();
+        std::fs::File::open(path)
+            .expect("Couldn't open ghost")
+            .read_to_end(&mut rkg_data)
+            .expect("Couldn't read bytes in file");
+
+        let ghost = Ghost::read_from(Cursor::new(&rkg_data)).expect("Couldn't stream ghost");
+        assert_eq!(ghost.bytes(), rkg_data);
+        assert_eq!(
+            ghost.ctgp_metadata().is_some(),
+            Ghost::new(&rkg_data).unwrap().ctgp_metadata().is_some()
+        );
+        let mut written = Vec::new();
+        ghost.write_to(&mut written).unwrap();
+        assert_eq!(written, rkg_data);
+
+        // Sections stop right where they end: the input data ends 4 bytes before the CRC32
+        let mut reader = Cursor::new(&rkg_data);
+        let header = Header::read_from(&mut reader).unwrap();
+        assert_eq!(reader.position(), 0x88);
+        let input_data = InputData::read_from(&mut reader, &header).unwrap();
+        let rkg_end = reader.position() as usize + 0x04;
+        assert_eq!(
+            crc32(&rkg_data[..rkg_end - 0x04]).to_be_bytes(),
+            rkg_data[rkg_end - 0x04..rkg_end]
+        );
+        assert_eq!(input_data.frame_count(), ghost.input_data().frame_count());
+
+        let mut written = Vec::new();
+        header.write_to(&mut written).unwrap();
+        input_data.write_to(&mut written, &header).unwrap();
+        let reread = InputData::read_from(&written[0x88..], &header).unwrap();
+        assert_eq!(reread.raw_input_data(), input_data.raw_input_data());
+        assert_eq!(written[..0x88], rkg_data[..0x88]);
+
+        assert!(matches!(
+            Ghost::read_from(Cursor::new(&rkg_data[..rkg_end - 0x02])),
+            Err(GhostError::IoError(err)) if err.kind() == ErrorKind::UnexpectedEof
+        ));
+    }
+}
+
+#[cfg(feature = "library")]
+#[test]
+fn test_library() {
+    use crate::library::{GhostLibrary, ImportOutcome};
+
+    let root = std::env::temp_dir().join(format!("rkg_utils_library_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let mut library = GhostLibrary::open(&root).expect("Couldn't open library");
+    assert!(library.is_empty().unwrap());
+
+    let results = library.import_directory("./test_ghosts").unwrap();
+    assert!(results.iter().any(|(_, result)| result.is_err()));
+    let added = results
+        .iter()
+        .filter(|(_, result)| matches!(result, Ok((_, ImportOutcome::Added))))
+        .count();
+    assert_eq!(library.len().unwrap(), added);
+
+    // Same file again is only stored once
+    let (hash, outcome) = library.import_file("./test_ghosts/JC_LC.rkg").unwrap();
+    assert_eq!(outcome, ImportOutcome::AlreadyPresent);
+    assert_eq!(library.len().unwrap(), added);
+
+    let ghost = library.ghost(&hash).unwrap();
+    assert_eq!(
+        ghost.bytes(),
+        std::fs::read("./test_ghosts/JC_LC.rkg").unwrap()
+    );
+    let entry = library.entry(&hash).unwrap().unwrap();
+    assert_eq!(entry.slot_id(), ghost.header().slot_id());
+    assert_eq!(entry.finish_time(), *ghost.header().finish_time());
+    assert_eq!(entry.mii_name(), ghost.header().mii().name());
+
+    let best = library.best_per_track(1, None).unwrap();
+    assert!(best.len() <= added);
+    assert!(
+        best.iter()
+            .any(|entry| entry.slot_id() == ghost.header().slot_id())
+    );
+    for track in &best {
+        let slower = library.by_track(track.slot_id()).unwrap();
+        assert!(
+            slower
+                .iter()
+                .all(|other| other.finish_time() >= track.finish_time()
+                    || other.track_sha1() != track.track_sha1()
+                    || other.category() != track.category())
+        );
+    }
+
+    if let Some(player_id) = ghost.ctgp_metadata().map(|metadata| metadata.player_id()) {
+        assert!(
+            library
+                .by_player(player_id)
+                .unwrap()
+                .iter()
+                .any(|entry| entry.hash() == hash)
+        );
+    }
+    let mii = ghost.header().mii();
+    assert!(
+        library
+            .by_mii(mii.system_id().raw(), mii.mii_id().raw())
+            .unwrap()
+            .iter()
+            .any(|entry| entry.hash() == hash)
+    );
+
+    drop(library);
+    // Reopening keeps the index
+    assert_eq!(GhostLibrary::open(&root).unwrap().len().unwrap(), added);
+    std::fs::remove_dir_all(&root).unwrap();
+
+    // A file that can't be moved into place leaves neither an index entry nor a temporary file
+    let mut library = GhostLibrary::open(&root).expect("Couldn't open library");
+    let object_path = root
+        .join("objects")
+        .join(&hash[..2])
+        .join(format!("{}.rkg", &hash[2..]));
+    std::fs::create_dir_all(object_path.join("blocker")).unwrap();
+    assert!(library.import_file("./test_ghosts/JC_LC.rkg").is_err());
+    assert!(library.is_empty().unwrap());
+    assert!(!object_path.with_extension("rkg.tmp").exists());
+
+    std::fs::remove_dir_all(&object_path).unwrap();
+    let (_, outcome) = library.import_file("./test_ghosts/JC_LC.rkg").unwrap();
+    assert_eq!(outcome, ImportOutcome::Added);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_leaderboard() {
+    use crate::leaderboard::{
+        BoardKind, Leaderboard, PlayerKey,
+        export::{to_csv, to_json, to_markdown},
+    };
+
+    let mut paths: Vec<_> = std::fs::read_dir("./test_ghosts")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+    let ghosts: Vec<Ghost> = paths
+        .iter()
+        .filter_map(|path| Ghost::new_from_file(path).ok())
+        .collect();
+
+    // The same run twice, and once more compressed, only counts once
+    let jc_lc = Ghost::new_from_file("./test_ghosts/JC_LC.rkg").unwrap();
+    let repeated = [jc_lc.clone(), jc_lc.clone()];
+    let boards = Leaderboard::build(&repeated, BoardKind::Finish);
+    assert_eq!(boards.len(), 1);
+    assert_eq!(boards[0].entries().len(), 1);
+    assert_eq!(boards[0].entries()[0].rank(), 1);
+    assert_eq!(boards[0].entries()[0].ghost_index(), 0);
+    assert_eq!(boards[0].entries()[0].player(), PlayerKey::of(&jc_lc));
+
+    let flaps = Leaderboard::build(&repeated, BoardKind::FastestLap);
+    let flap = &flaps[0].entries()[0];
+    assert_eq!(
+        Some(flap.time()),
+        jc_lc.header().lap_split_times().iter().min().copied()
+    );
+    assert!(flap.lap().is_some_and(|lap| (1..=3).contains(&lap)));
+
+    for kind in [BoardKind::Finish, BoardKind::FastestLap] {
+        let boards = Leaderboard::build(&ghosts, kind);
+        let entry_count: usize = boards.iter().map(|board| board.entries().len()).sum();
+        assert!(entry_count <= ghosts.len());
+
+        for board in &boards {
+            let entries = board.entries();
+            assert_eq!(entries[0].rank(), 1);
+            for pair in entries.windows(2) {
+                assert!(pair[0].rank() <= pair[1].rank());
+                assert_ne!(pair[0].player(), pair[1].player());
+                assert!(pair[0].exact_time().is_some() == board.category().is_some());
+            }
+            for entry in entries {
+                let ghost = &ghosts[entry.ghost_index()];
+                assert_eq!(PlayerKey::of(ghost), entry.player());
+                assert_eq!(ghost.header().slot_id(), board.slot_id());
+            }
+        }
+
+        let json = to_json(&boards);
+        assert!(json.starts_with('[') && json.ends_with(']'));
+        assert_eq!(json.matches("\"rank\":").count(), entry_count);
+
+        let csv = to_csv(&boards);
+        assert_eq!(csv.lines().count(), entry_count + 1);
+        assert!(csv.starts_with("board,track,track_sha1,category,rank,player"));
+
+        let markdown = to_markdown(&boards);
+        assert_eq!(markdown.matches("## ").count(), boards.len());
+        assert_eq!(
+            markdown
+                .lines()
+                .filter(|line| line.starts_with("| "))
+                .count(),
+            entry_count + boards.len() * 2
+        );
+    }
+}
+
+#[test]
+fn test_identity() {
+    use crate::identity::{Identities, IdentityConflict, LinkReason};
+
+    let mut paths: Vec<_> = std::fs::read_dir("./test_ghosts")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+    let mut ghosts: Vec<Ghost> = paths
+        .iter()
+        .filter_map(|path| Ghost::new_from_file(path).ok())
+        .collect();
+
+    let identities = Identities::resolve(&ghosts);
+    let grouped: usize = identities
+        .identities()
+        .iter()
+        .map(|identity| identity.ghost_indices().len())
+        .sum();
+    assert_eq!(grouped, ghosts.len());
+    // The two chϊcκεη ghosts share a Mii but not their player ID
+    let [
+        IdentityConflict::MiiOnSeveralPlayerIds {
+            player_ids,
+            ghost_indices,
+            ..
+        },
+    ] = identities.conflicts()
+    else {
+        std::panic!(
+            "Expected a single conflict, got {:?}",
+            identities.conflicts()
+        );
+    };
+    assert_eq!(player_ids.len(), 2);
+    assert_eq!(ghost_indices.len(), 2);
+    assert!(
+        ghost_indices
+            .iter()
+            .all(|index| ghosts[*index].header().mii().name() == "chϊcκεη")
+    );
+    let chain = identities
+        .explain(ghost_indices[0], ghost_indices[1])
+        .unwrap();
+    assert!(matches!(chain[..], [link] if matches!(link.reason(), LinkReason::Mii { .. })));
+
+    // Both JC_LC files were driven with the same Mii, only one of them has a CTGP footer
+    let jc_lc = ghosts
+        .iter()
+        .position(|ghost| ghost.header().mii().name() == "JC" && ghost.ctgp_metadata().is_some())
+        .unwrap();
+    let identity = identities.identity_of(jc_lc).unwrap();
+    assert!(identity.ghost_indices().len() >= 2);
+    assert_eq!(identity.names(), ["JC"]);
+    for other in identity.ghost_indices() {
+        let chain = identities.explain(jc_lc, *other).unwrap();
+        assert_eq!(chain.is_empty(), *other == jc_lc);
+        assert!(chain.iter().all(|link| link.reason().is_strong()));
+    }
+
+    // The same account on another console, renamed: still linked, but flagged
+    let mut bytes = ghosts[jc_lc].bytes().to_vec();
+    bytes[0x3C + 0x1C..0x3C + 0x20].copy_from_slice(&0x1234_5678u32.to_be_bytes());
+    ghosts.push(Ghost::new(&bytes).unwrap());
+    let moved = ghosts.len() - 1;
+
+    let identities = Identities::resolve(&ghosts);
+    let identity = identities.identity_of(moved).unwrap();
+    assert!(identity.ghost_indices().contains(&jc_lc));
+    assert_eq!(identity.system_ids().len(), 2);
+
+    let chain = identities.explain(jc_lc, moved).unwrap();
+    assert_eq!(chain.len(), 1);
+    let player_id = ghosts[jc_lc].ctgp_metadata().unwrap().player_id();
+    assert_eq!(chain[0].reason(), &LinkReason::PlayerId(player_id));
+    assert!(chain[0].to_string().contains("same CTGP player ID"));
+
+    let conflict = identities
+        .conflicts()
+        .iter()
+        .find(|conflict| conflict.ghost_indices().contains(&moved))
+        .unwrap();
+    assert!(matches!(
+        conflict,
+        IdentityConflict::PlayerIdOnSeveralConsoles { player_id: id, system_ids, .. }
+            if *id == player_id && system_ids.len() == 2
+    ));
+    assert!(identities.explain(jc_lc, moved + 1).is_none());
+
+    // A ghost of the same account saved without a Mii isn't another console
+    ghosts.pop();
+    let mut bytes = ghosts[jc_lc].bytes().to_vec();
+    bytes[0x3C + 0x18..0x3C + 0x20].fill(0);
+    ghosts.push(Ghost::new(&bytes).unwrap());
+    let mii_less = ghosts.len() - 1;
+
+    let identities = Identities::resolve(&ghosts);
+    let identity = identities.identity_of(mii_less).unwrap();
+    assert!(identity.ghost_indices().contains(&jc_lc));
+    assert_eq!(identity.system_ids().len(), 1);
+    assert!(
+        identities.conflicts().iter().all(|conflict| !matches!(
+            conflict,
+            IdentityConflict::PlayerIdOnSeveralConsoles { .. }
+        ))
+    );
+}
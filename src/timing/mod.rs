@@ -0,0 +1,307 @@
+use alloc::vec::Vec;
+use core::{fmt::Display, ops::RangeInclusive};
+
+use crate::{
+    ctgp_metadata::{CTGPMetadata, exact_finish_time::ExactFinishTime},
+    header::{Header, in_game_time::InGameTime},
+    input_data::InputData,
+    timing::race_clock::RaceClock,
+};
+
+pub mod race_clock;
+
+/// One way in which the times stored in a ghost disagree with each other
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TimingIssue {
+    /// A time the race timer can't display (seconds > 59, milliseconds > 999 or over 5:59.999)
+    InvalidFinishTime {
+        finish_time: InGameTime,
+    },
+    InvalidLapTime {
+        lap: usize,
+        lap_time: InGameTime,
+    },
+    /// Lap splits are differences between rounded cumulative times, so they always add up exactly.
+    /// `lap_sum` stops at `InGameTime::MAX`.
+    LapSumMismatch {
+        finish_time: InGameTime,
+        lap_sum: InGameTime,
+    },
+    /// CTGP's exact finish time doesn't round to the displayed finish time
+    ExactFinishTimeMismatch {
+        finish_time: InGameTime,
+        exact_finish_time: ExactFinishTime,
+    },
+    /// CTGP's exact lap time doesn't produce the displayed lap split
+    ExactLapTimeMismatch {
+        lap: usize,
+        lap_time: InGameTime,
+        expected: InGameTime,
+    },
+    ExactLapSumMismatch {
+        exact_finish_time: ExactFinishTime,
+        exact_lap_sum: ExactFinishTime,
+    },
+    ExactLapCountMismatch {
+        lap_count: usize,
+        exact_lap_count: usize,
+    },
+    /// Face button, stick and DPad streams must all cover the same amount of frames
+    InputStreamLengthMismatch {
+        face_frames: u32,
+        stick_frames: u32,
+        dpad_frames: u32,
+    },
+    /// Inputs don't cover the countdown plus the frames needed to reach the finish time
+    InputFrameCountMismatch {
+        input_frames: u32,
+        expected: RangeInclusive<u32>,
+    },
+}
+
+impl Display for TimingIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidFinishTime { finish_time } => {
+                write!(
+                    f,
+                    "Finish time {finish_time} can't be displayed by the game"
+                )
+            }
+            Self::InvalidLapTime { lap, lap_time } => write!(
+                f,
+                "Lap {} time {lap_time} can't be displayed by the game",
+                lap + 1
+            ),
+            Self::LapSumMismatch {
+                finish_time,
+                lap_sum,
+            } => write!(
+                f,
+                "Lap splits add up to {lap_sum}, finish time is {finish_time}"
+            ),
+            Self::ExactFinishTimeMismatch {
+                finish_time,
+                exact_finish_time,
+            } => write!(
+                f,
+                "Exact finish time {exact_finish_time} doesn't round to finish time {finish_time}"
+            ),
+            Self::ExactLapTimeMismatch {
+                lap,
+                lap_time,
+                expected,
+            } => write!(
+                f,
+                "Lap {} time is {lap_time}, exact lap times give {expected}",
+                lap + 1
+            ),
+            Self::ExactLapSumMismatch {
+                exact_finish_time,
+                exact_lap_sum,
+            } => write!(
+                f,
+                "Exact lap times add up to {exact_lap_sum}, exact finish time is {exact_finish_time}"
+            ),
+            Self::ExactLapCountMismatch {
+                lap_count,
+                exact_lap_count,
+            } => write!(
+                f,
+                "Header has {lap_count} laps, CTGP metadata has {exact_lap_count}"
+            ),
+            Self::InputStreamLengthMismatch {
+                face_frames,
+                stick_frames,
+                dpad_frames,
+            } => write!(
+                f,
+                "Input streams differ in length: {face_frames} face, {stick_frames} stick, {dpad_frames} DPad frames"
+            ),
+            Self::InputFrameCountMismatch {
+                input_frames,
+                expected,
+            } => write!(
+                f,
+                "Inputs last {input_frames} frames, expected {}..={}",
+                expected.start(),
+                expected.end()
+            ),
+        }
+    }
+}
+
+/// Cross-check of every time stored in a ghost: header finish/lap times, CTGP exact times and
+/// the length of the input data. Mismatches are the strongest sign of a hex-edited time.
+#[derive(Clone, Debug)]
+pub struct TimingReport {
+    lap_sum: InGameTime,
+    input_frames: u32,
+    expected_input_frames: RangeInclusive<u32>,
+    issues: Vec<TimingIssue>,
+}
+
+impl TimingReport {
+    pub fn new(
+        header: &Header,
+        input_data: &InputData,
+        ctgp_metadata: Option<&CTGPMetadata>,
+    ) -> Self {
+        let mut issues = Vec::new();
+        let finish_time = *header.finish_time();
+        let lap_times = header.lap_split_times();
+
+        if !finish_time.is_technically_valid() {
+            issues.push(TimingIssue::InvalidFinishTime { finish_time });
+        }
+        for (lap, lap_time) in lap_times.iter().enumerate() {
+            if !lap_time.is_technically_valid() {
+                issues.push(TimingIssue::InvalidLapTime {
+                    lap,
+                    lap_time: *lap_time,
+                });
+            }
+        }
+
+        // Hex-edited laps can add up past what an `InGameTime` holds, that still has to mismatch
+        let lap_sum = lap_times
+            .iter()
+            .fold(InGameTime::default(), |sum, lap| sum.saturating_add(*lap));
+        if lap_sum != finish_time {
+            issues.push(TimingIssue::LapSumMismatch {
+                finish_time,
+                lap_sum,
+            });
+        }
+
+        if let Some(ctgp_metadata) = ctgp_metadata {
+            check_exact_times(finish_time, lap_times, ctgp_metadata, &mut issues);
+        }
+
+        let face_frames = input_data.frame_count();
+        let stick_frames = input_data
+            .stick_inputs()
+            .iter()
+            .map(|i| i.frame_duration())
+            .sum();
+        let dpad_frames = input_data
+            .dpad_inputs()
+            .iter()
+            .map(|i| i.frame_duration())
+            .sum();
+        if face_frames != stick_frames || face_frames != dpad_frames {
+            issues.push(TimingIssue::InputStreamLengthMismatch {
+                face_frames,
+                stick_frames,
+                dpad_frames,
+            });
+        }
+
+        let input_frames = face_frames;
+        let race_clock = RaceClock::default();
+        let expected_input_frames = match ctgp_metadata {
+            Some(ctgp_metadata) => {
+                let frame = race_clock.time_to_frame(ctgp_metadata.exact_finish_time());
+                frame..=frame
+            }
+            None => race_clock.in_game_time_to_frames(finish_time),
+        };
+        if !expected_input_frames.contains(&input_frames) {
+            issues.push(TimingIssue::InputFrameCountMismatch {
+                input_frames,
+                expected: expected_input_frames.clone(),
+            });
+        }
+
+        Self {
+            lap_sum,
+            input_frames,
+            expected_input_frames,
+            issues,
+        }
+    }
+
+    /// Returns true if every stored time agrees with every other one
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Sum of the lap split times, at most `InGameTime::MAX`
+    pub fn lap_sum(&self) -> InGameTime {
+        self.lap_sum
+    }
+
+    /// Length of the input data in frames, including the countdown
+    pub fn input_frames(&self) -> u32 {
+        self.input_frames
+    }
+
+    /// Input lengths that are possible for the ghost's finish time
+    pub fn expected_input_frames(&self) -> &RangeInclusive<u32> {
+        &self.expected_input_frames
+    }
+
+    pub fn issues(&self) -> &[TimingIssue] {
+        &self.issues
+    }
+}
+
+fn check_exact_times(
+    finish_time: InGameTime,
+    lap_times: &[InGameTime],
+    ctgp_metadata: &CTGPMetadata,
+    issues: &mut Vec<TimingIssue>,
+) {
+    let exact_finish_time = ctgp_metadata.exact_finish_time();
+    let exact_lap_times = ctgp_metadata.exact_lap_times();
+
+    if exact_finish_time.to_in_game_time().ok() != Some(finish_time) {
+        issues.push(TimingIssue::ExactFinishTimeMismatch {
+            finish_time,
+            exact_finish_time,
+        });
+    }
+
+    if exact_lap_times.len() != lap_times.len() {
+        issues.push(TimingIssue::ExactLapCountMismatch {
+            lap_count: lap_times.len(),
+            exact_lap_count: exact_lap_times.len(),
+        });
+        return;
+    }
+
+    let exact_lap_sum = exact_lap_times
+        .iter()
+        .try_fold(ExactFinishTime::default(), |sum, lap| sum.checked_add(*lap));
+    if exact_lap_sum != Some(exact_finish_time) {
+        issues.push(TimingIssue::ExactLapSumMismatch {
+            exact_finish_time,
+            exact_lap_sum: exact_lap_sum.unwrap_or_default(),
+        });
+    }
+
+    // The game rounds the cumulative time up at every lap line and displays the differences
+    let mut exact_elapsed = ExactFinishTime::default();
+    let mut displayed_elapsed = 0u32;
+    for (lap, (lap_time, exact_lap_time)) in lap_times.iter().zip(exact_lap_times).enumerate() {
+        let Some(elapsed) = exact_elapsed.checked_add(*exact_lap_time) else {
+            break;
+        };
+        exact_elapsed = elapsed;
+        let Ok(rounded) = exact_elapsed.to_in_game_time() else {
+            break;
+        };
+        let rounded = rounded.igt_to_millis() as u32;
+
+        let expected =
+            InGameTime::from_millis(rounded.saturating_sub(displayed_elapsed)).unwrap_or_default();
+        if expected != *lap_time {
+            issues.push(TimingIssue::ExactLapTimeMismatch {
+                lap,
+                lap_time: *lap_time,
+                expected,
+            });
+        }
+        displayed_elapsed = rounded;
+    }
+}
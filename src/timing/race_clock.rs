@@ -0,0 +1,98 @@
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use crate::{
+    ctgp_metadata::exact_finish_time::ExactFinishTime,
+    header::{Header, in_game_time::InGameTime},
+};
+
+const PICOSECONDS_PER_MILLISECOND: u64 = 1_000_000_000;
+
+/// Model of how Mario Kart Wii keeps time. Frames are counted from the first frame of input data,
+/// starting at 1, so frame `countdown_frames` is the last frame of the countdown and the race timer
+/// reads zero at its end. Every later frame adds 1001/60 ms (the game runs at 59.94 Hz), and the
+/// finish line is crossed somewhere during the last frame of a ghost's inputs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RaceClock {
+    countdown_frames: u32,
+}
+
+impl Default for RaceClock {
+    fn default() -> Self {
+        Self::new(Self::COUNTDOWN_FRAMES)
+    }
+}
+
+impl RaceClock {
+    /// Length of the 3-2-1-GO countdown in every race
+    pub const COUNTDOWN_FRAMES: u32 = 240;
+    /// Frames per second, 60/1.001
+    pub const FRAME_RATE: f64 = 60.0 / 1.001;
+    /// Frame length is FRAME_DURATION_NUMERATOR / FRAME_DURATION_DENOMINATOR milliseconds
+    pub const FRAME_DURATION_NUMERATOR: u64 = 1001;
+    pub const FRAME_DURATION_DENOMINATOR: u64 = 60;
+
+    pub fn new(countdown_frames: u32) -> Self {
+        Self { countdown_frames }
+    }
+
+    pub fn countdown_frames(self) -> u32 {
+        self.countdown_frames
+    }
+
+    /// Race timer at the end of `frame`, None for frames during the countdown
+    pub fn frame_to_time(self, frame: u32) -> Option<ExactFinishTime> {
+        let race_frames = frame.checked_sub(self.countdown_frames)? as u64;
+        let picoseconds = race_frames as u128
+            * (Self::FRAME_DURATION_NUMERATOR * PICOSECONDS_PER_MILLISECOND) as u128
+            / Self::FRAME_DURATION_DENOMINATOR as u128;
+
+        ExactFinishTime::from_picoseconds(u64::try_from(picoseconds).ok()?).ok()
+    }
+
+    /// The frame during which the race timer reaches `time`
+    pub fn time_to_frame(self, time: ExactFinishTime) -> u32 {
+        self.picoseconds_to_frame(time.total_picoseconds())
+    }
+
+    /// Frames during which the timer could have reached a time the game displays as `time`.
+    /// Displayed times are rounded up, so the exact time is anywhere in (time - 1ms, time]
+    pub fn in_game_time_to_frames(self, time: InGameTime) -> RangeInclusive<u32> {
+        let millis = time.igt_to_millis() as u64;
+        let first = match millis {
+            0 => self.countdown_frames,
+            millis => self.countdown_frames.saturating_add(
+                ((millis - 1) * Self::FRAME_DURATION_DENOMINATOR / Self::FRAME_DURATION_NUMERATOR)
+                    as u32
+                    + 1,
+            ),
+        };
+
+        first..=self.time_to_frame(time.into())
+    }
+
+    /// Frames on which each lap of the ghost ends, the last one being the final frame of input.
+    /// Headers only store milliseconds, so when two frames are possible the later one is used.
+    pub fn lap_boundaries(self, header: &Header) -> Vec<u32> {
+        let mut elapsed: u64 = 0;
+
+        header
+            .lap_split_times()
+            .iter()
+            .map(|lap_time| {
+                elapsed =
+                    elapsed.saturating_add(ExactFinishTime::from(*lap_time).total_picoseconds());
+                self.picoseconds_to_frame(elapsed)
+            })
+            .collect()
+    }
+
+    /// Saturates at `u32::MAX` for times no ghost could have
+    fn picoseconds_to_frame(self, picoseconds: u64) -> u32 {
+        let race_frames = (picoseconds as u128 * Self::FRAME_DURATION_DENOMINATOR as u128)
+            .div_ceil((Self::FRAME_DURATION_NUMERATOR * PICOSECONDS_PER_MILLISECOND) as u128);
+
+        self.countdown_frames
+            .saturating_add(u32::try_from(race_frames).unwrap_or(u32::MAX))
+    }
+}
@@ -0,0 +1,366 @@
+//! `wasm-bindgen` exports to parse ghosts in the browser. Every function takes the file as a
+//! `Uint8Array` and returns a plain object, with enums as their variant names and times both as
+//! numbers and as text.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    Ghost, GhostError,
+    audit::{AuditReport, audit},
+    ctgp_metadata::{CTGPMetadata, exact_finish_time::ExactFinishTime},
+    header::{Header, in_game_time::InGameTime, mii::Mii},
+    input_data::InputData,
+    integrity::{IntegrityReport, verify_integrity},
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TimeView {
+    pub(crate) minutes: u8,
+    pub(crate) seconds: u8,
+    pub(crate) milliseconds: u16,
+    pub(crate) text: String,
+}
+
+impl From<InGameTime> for TimeView {
+    fn from(value: InGameTime) -> Self {
+        Self {
+            minutes: value.minutes(),
+            seconds: value.seconds(),
+            milliseconds: value.milliseconds(),
+            text: value.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ExactTimeView {
+    pub(crate) minutes: u8,
+    pub(crate) seconds: u8,
+    pub(crate) picoseconds: u64,
+    pub(crate) text: String,
+}
+
+impl From<ExactFinishTime> for ExactTimeView {
+    fn from(value: ExactFinishTime) -> Self {
+        Self {
+            minutes: value.minutes(),
+            seconds: value.seconds(),
+            picoseconds: value.picoseconds(),
+            text: value.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MiiView {
+    pub(crate) name: String,
+    pub(crate) display_name: String,
+    pub(crate) creator_name: String,
+    pub(crate) is_girl: bool,
+    pub(crate) is_favorite: bool,
+    pub(crate) favorite_color: String,
+    pub(crate) mii_id: String,
+    pub(crate) system_id: String,
+    pub(crate) mingle_off: bool,
+    pub(crate) downloaded: bool,
+}
+
+impl From<&Mii> for MiiView {
+    fn from(value: &Mii) -> Self {
+        Self {
+            name: value.name().to_string(),
+            display_name: value.display_name(),
+            creator_name: value.creator_name().to_string(),
+            is_girl: value.is_girl(),
+            is_favorite: value.is_favorite(),
+            favorite_color: format!("{:?}", value.favorite_color()),
+            mii_id: value.mii_id().to_string(),
+            system_id: value.system_id().to_string(),
+            mingle_off: value.mingle_off(),
+            downloaded: value.downloaded(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HeaderView {
+    pub(crate) finish_time: TimeView,
+    pub(crate) lap_split_times: Vec<TimeView>,
+    pub(crate) track: String,
+    pub(crate) character: String,
+    pub(crate) vehicle: String,
+    pub(crate) controller: String,
+    pub(crate) ghost_type: String,
+    pub(crate) year: u16,
+    pub(crate) month: u8,
+    pub(crate) day: u8,
+    pub(crate) is_compressed: bool,
+    pub(crate) is_automatic_drift: bool,
+    pub(crate) country_id: u8,
+    pub(crate) mii: MiiView,
+    pub(crate) mii_crc16_valid: bool,
+}
+
+impl From<&Header> for HeaderView {
+    fn from(value: &Header) -> Self {
+        Self {
+            finish_time: (*value.finish_time()).into(),
+            lap_split_times: value
+                .lap_split_times()
+                .iter()
+                .map(|lap| TimeView::from(*lap))
+                .collect(),
+            track: format!("{:?}", value.slot_id()),
+            character: format!("{:?}", value.combo().character()),
+            vehicle: format!("{:?}", value.combo().vehicle()),
+            controller: format!("{:?}", value.controller()),
+            ghost_type: format!("{:?}", value.ghost_type()),
+            year: value.date_set().year(),
+            month: value.date_set().month(),
+            day: value.date_set().day(),
+            is_compressed: value.is_compressed(),
+            is_automatic_drift: value.is_automatic_drift(),
+            country_id: value.country_id(),
+            mii: value.mii().into(),
+            mii_crc16_valid: value.verify_mii_crc16(),
+        }
+    }
+}
+
+/// One run of identical inputs, held for `frames` frames
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct InputView {
+    pub(crate) buttons: Vec<String>,
+    pub(crate) stick_x: i8,
+    pub(crate) stick_y: i8,
+    pub(crate) dpad: String,
+    pub(crate) frames: u32,
+}
+
+/// Controller state during a single frame, as in `InputData::frame_records`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FrameView {
+    /// Face button bits as stored in the file
+    pub(crate) buttons: u8,
+    pub(crate) stick_x: i8,
+    pub(crate) stick_y: i8,
+    /// 0 for none, then up, down, left and right
+    pub(crate) dpad: u8,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct InputDataView {
+    pub(crate) frame_count: u32,
+    pub(crate) inputs: Vec<InputView>,
+    /// One entry per frame, like `InputData.frame_inputs()` in the Python bindings
+    pub(crate) frame_inputs: Vec<FrameView>,
+}
+
+impl From<&InputData> for InputDataView {
+    fn from(value: &InputData) -> Self {
+        Self {
+            frame_count: value.frame_count(),
+            inputs: value
+                .inputs()
+                .iter()
+                .map(|input| InputView {
+                    buttons: input
+                        .face_buttons()
+                        .iter()
+                        .map(|button| format!("{button:?}"))
+                        .collect(),
+                    stick_x: input.stick_x(),
+                    stick_y: input.stick_y(),
+                    dpad: format!("{:?}", input.dpad_button()),
+                    frames: input.frame_duration(),
+                })
+                .collect(),
+            frame_inputs: value
+                .frame_records()
+                .chunks_exact(4)
+                .map(|record| FrameView {
+                    buttons: record[0],
+                    stick_x: record[1] as i8,
+                    stick_y: record[2] as i8,
+                    dpad: record[3],
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CTGPMetadataView {
+    /// Hex, a `u64` doesn't fit in a JS number
+    pub(crate) player_id: String,
+    pub(crate) track_sha1: String,
+    pub(crate) category: String,
+    pub(crate) ctgp_version: Option<String>,
+    pub(crate) exact_finish_time: ExactTimeView,
+    pub(crate) exact_lap_times: Vec<ExactTimeView>,
+    pub(crate) pause_frames: Vec<u32>,
+    pub(crate) my_stuff_enabled: bool,
+    pub(crate) my_stuff_used: bool,
+    pub(crate) usb_gamecube_enabled: bool,
+    pub(crate) cannoned: bool,
+    pub(crate) went_oob: bool,
+    pub(crate) has_slowdown: bool,
+    pub(crate) has_rapidfire: bool,
+    pub(crate) dubious_ghost: bool,
+    pub(crate) has_mii_data_replaced: bool,
+    pub(crate) has_name_replaced: bool,
+    pub(crate) respawns: bool,
+}
+
+impl From<&CTGPMetadata> for CTGPMetadataView {
+    fn from(value: &CTGPMetadata) -> Self {
+        Self {
+            player_id: format!("{:016X}", value.player_id()),
+            track_sha1: value
+                .track_sha1()
+                .iter()
+                .map(|byte| format!("{byte:02X}"))
+                .collect(),
+            category: format!("{:?}", value.category()),
+            ctgp_version: value.ctgp_version().map(|version| version.to_string()),
+            exact_finish_time: value.exact_finish_time().into(),
+            exact_lap_times: value
+                .exact_lap_times()
+                .iter()
+                .map(|lap| ExactTimeView::from(*lap))
+                .collect(),
+            pause_frames: value.pause_frames().clone(),
+            my_stuff_enabled: value.my_stuff_enabled(),
+            my_stuff_used: value.my_stuff_used(),
+            usb_gamecube_enabled: value.usb_gamecube_enabled(),
+            cannoned: value.cannoned(),
+            went_oob: value.went_oob(),
+            has_slowdown: value.has_slowdown(),
+            has_rapidfire: value.has_rapidfire(),
+            dubious_ghost: value.dubious_ghost(),
+            has_mii_data_replaced: value.has_mii_data_replaced(),
+            has_name_replaced: value.has_name_replaced(),
+            respawns: value.respawns(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FindingView {
+    pub(crate) code: &'static str,
+    pub(crate) severity: String,
+    pub(crate) message: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AuditView {
+    pub(crate) verdict: String,
+    pub(crate) findings: Vec<FindingView>,
+}
+
+impl From<&AuditReport> for AuditView {
+    fn from(value: &AuditReport) -> Self {
+        Self {
+            verdict: format!("{:?}", value.verdict()),
+            findings: value
+                .findings()
+                .iter()
+                .map(|finding| FindingView {
+                    code: finding.code(),
+                    severity: finding.severity().to_string(),
+                    message: finding.message().to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct IntegrityView {
+    pub(crate) is_intact: bool,
+    pub(crate) issues: Vec<String>,
+}
+
+impl From<&IntegrityReport> for IntegrityView {
+    fn from(value: &IntegrityReport) -> Self {
+        Self {
+            is_intact: value.is_intact(),
+            issues: value.issues().iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GhostView {
+    pub(crate) header: HeaderView,
+    pub(crate) input_data: InputDataView,
+    pub(crate) ctgp_metadata: Option<CTGPMetadataView>,
+    pub(crate) integrity: IntegrityView,
+    pub(crate) audit: AuditView,
+}
+
+impl From<&Ghost> for GhostView {
+    fn from(value: &Ghost) -> Self {
+        Self {
+            header: value.header().into(),
+            input_data: value.input_data().into(),
+            ctgp_metadata: value.ctgp_metadata().map(CTGPMetadataView::from),
+            integrity: (&value.verify_integrity()).into(),
+            audit: (&value.audit()).into(),
+        }
+    }
+}
+
+pub(crate) fn ghost_view(bytes: &[u8]) -> Result<GhostView, GhostError> {
+    Ghost::new(bytes).map(|ghost| GhostView::from(&ghost))
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsError> {
+    // Plain objects instead of `Map`s, so the result can go through `JSON.stringify`
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    Ok(value.serialize(&serializer)?)
+}
+
+/// Parses a whole ghost, with its integrity and audit results
+#[wasm_bindgen(js_name = parseGhost)]
+pub fn parse_ghost(bytes: &[u8]) -> Result<JsValue, JsError> {
+    to_js(&ghost_view(bytes)?)
+}
+
+/// Parses only the 0x88 byte header, works on files with broken input data
+#[wasm_bindgen(js_name = parseHeader)]
+pub fn parse_header(bytes: &[u8]) -> Result<JsValue, JsError> {
+    let header = Header::new(bytes.get(..0x88).ok_or(GhostError::TooShort)?)?;
+    to_js(&HeaderView::from(&header))
+}
+
+/// Runs `audit::audit`, works on files that don't parse
+#[wasm_bindgen(js_name = auditGhost)]
+pub fn audit_ghost(bytes: &[u8]) -> Result<JsValue, JsError> {
+    to_js(&AuditView::from(&audit(bytes)))
+}
+
+/// Runs `integrity::verify_integrity`, works on files that don't parse
+#[wasm_bindgen(js_name = verifyIntegrity)]
+pub fn verify_ghost_integrity(bytes: &[u8]) -> Result<JsValue, JsError> {
+    to_js(&IntegrityView::from(&verify_integrity(bytes)))
+}